@@ -33,6 +33,7 @@ pub enum Icons {
     Clipboard,
     Languages,
     Save,
+    Star,
     Trash,
     Trash2,
 }
@@ -44,6 +45,7 @@ impl IconNamed for Icons {
             Icons::Clipboard => "icons/clipboard.svg",
             Icons::Languages => "icons/languages.svg",
             Icons::Save => "icons/save.svg",
+            Icons::Star => "icons/star.svg",
             Icons::Trash => "icons/trash.svg",
             Icons::Trash2 => "icons/trash-2.svg",
         }
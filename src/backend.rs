@@ -0,0 +1,498 @@
+use crate::fake::{FakeBackend, FakeOptions};
+use crate::llama_cpp::{self, CompletionRequest};
+use crate::model_preset::ModelPreset;
+use crate::ollama::{self, ChatRequest, ConnectionOptions, Error};
+use crate::openai::{self, ChatCompletionRequest};
+use crate::prompt::Prompt;
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+use futures_util::{FutureExt, StreamExt};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The API a server speaks.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    #[default]
+    Ollama,
+    /// `/v1/models` and `/v1/chat/completions`, as served by llama.cpp or LM Studio.
+    OpenAI,
+    /// The native `/completion` and `/props` API of llama.cpp's `llama-server`.
+    #[serde(rename = "llama.cpp")]
+    LlamaCpp,
+    /// Canned answers without a server, for demos and tests.
+    Fake,
+}
+
+impl BackendKind {
+    /// The kinds offered in the connection settings. The fake backend is only selected in the
+    /// config file or with `TRANSLATE_GEMMA_FAKE`.
+    pub const ALL: [BackendKind; 3] = [
+        BackendKind::Ollama,
+        BackendKind::OpenAI,
+        BackendKind::LlamaCpp,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BackendKind::Ollama => "Ollama",
+            BackendKind::OpenAI => "OpenAI",
+            BackendKind::LlamaCpp => "llama.cpp",
+            BackendKind::Fake => "Fake",
+        }
+    }
+}
+
+/// A model a server can translate with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvailableModel {
+    pub name: String,
+    /// The size on disk, when the server reports it.
+    pub size: Option<u64>,
+}
+
+/// What a server reports about itself. Servers leave out what they do not know.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerInfo {
+    pub version: Option<Version>,
+    /// The model the server has loaded, for servers that serve a single model.
+    pub model: Option<String>,
+    /// The context size of the loaded model, in tokens.
+    pub context_size: Option<u64>,
+}
+
+/// What the app needs from a server to translate.
+pub trait TranslationBackend {
+    fn list_models(&self) -> BoxFuture<'static, Result<Vec<AvailableModel>, Error>>;
+
+    /// Checks that the server is reachable, returning what it reports about itself.
+    fn health(&self) -> BoxFuture<'static, Result<ServerInfo, Error>>;
+
+    /// Starts translating, streaming the translation in chunks. Options of the preset that the
+    /// server does not support are left out.
+    fn translate(
+        &self,
+        model: &str,
+        prompt: &Prompt,
+        preset: &ModelPreset,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String, Error>>, Error>>;
+}
+
+impl TranslationBackend for ollama::Client {
+    fn list_models(&self) -> BoxFuture<'static, Result<Vec<AvailableModel>, Error>> {
+        let client = self.clone();
+
+        async move {
+            let models = client.list().await?;
+
+            Ok(models
+                .into_iter()
+                .map(|model| AvailableModel {
+                    name: model.name,
+                    size: u64::try_from(model.size).ok(),
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn health(&self) -> BoxFuture<'static, Result<ServerInfo, Error>> {
+        let client = self.clone();
+
+        async move {
+            let version = client.version().await?;
+
+            Ok(ServerInfo {
+                version: Some(version),
+                ..Default::default()
+            })
+        }
+        .boxed()
+    }
+
+    /// Translates through `/api/chat`, so the prompt is wrapped in the model's chat template.
+    fn translate(
+        &self,
+        model: &str,
+        prompt: &Prompt,
+        preset: &ModelPreset,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String, Error>>, Error>> {
+        let client = self.clone();
+        let mut request = ChatRequest::builder()
+            .model(model)
+            .stream_on()
+            .user(prompt.to_string())
+            .build();
+        request.options = preset.ollama_options();
+        request.keep_alive = preset.keep_alive.clone();
+
+        async move {
+            let stream = client.chat(request).await?;
+            Ok(stream
+                .map(|item| item.map(|response| response.message.content))
+                .boxed())
+        }
+        .boxed()
+    }
+}
+
+impl TranslationBackend for openai::Client {
+    fn list_models(&self) -> BoxFuture<'static, Result<Vec<AvailableModel>, Error>> {
+        let client = self.clone();
+
+        async move {
+            let models = client.models().await?;
+
+            Ok(models
+                .into_iter()
+                .map(|model| AvailableModel {
+                    name: model.id,
+                    size: None,
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn health(&self) -> BoxFuture<'static, Result<ServerInfo, Error>> {
+        let client = self.clone();
+
+        // There is no standard version endpoint, so listing the models has to do.
+        async move { client.models().await.map(|_| ServerInfo::default()) }.boxed()
+    }
+
+    fn translate(
+        &self,
+        model: &str,
+        prompt: &Prompt,
+        preset: &ModelPreset,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String, Error>>, Error>> {
+        let client = self.clone();
+        let mut request = ChatCompletionRequest::builder()
+            .model(model)
+            .user(prompt.to_string())
+            .build();
+        request.temperature = preset.temperature;
+        request.top_p = preset.top_p;
+        request.seed = preset.seed;
+        request.max_tokens = preset
+            .num_predict
+            .and_then(|num_predict| u64::try_from(num_predict).ok());
+
+        async move {
+            let stream = client.chat_completions(request).await?;
+            Ok(stream
+                .map(|item| item.map(|chunk| chunk.content().to_owned()))
+                .boxed())
+        }
+        .boxed()
+    }
+}
+
+impl TranslationBackend for llama_cpp::Client {
+    /// Lists the one model the server was started with.
+    fn list_models(&self) -> BoxFuture<'static, Result<Vec<AvailableModel>, Error>> {
+        let client = self.clone();
+
+        async move {
+            let props = client.props().await?;
+
+            Ok(props
+                .model_name()
+                .map(|name| AvailableModel { name, size: None })
+                .into_iter()
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn health(&self) -> BoxFuture<'static, Result<ServerInfo, Error>> {
+        let client = self.clone();
+
+        async move {
+            let props = client.props().await?;
+
+            Ok(ServerInfo {
+                version: None,
+                model: props.model_name(),
+                context_size: Some(props.default_generation_settings.n_ctx),
+            })
+        }
+        .boxed()
+    }
+
+    /// Ignores the model, since the server only has the one it was started with, and the
+    /// context size, which is fixed when the server starts.
+    fn translate(
+        &self,
+        _: &str,
+        prompt: &Prompt,
+        preset: &ModelPreset,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String, Error>>, Error>> {
+        let client = self.clone();
+        let mut request = CompletionRequest::builder()
+            .prompt(gemma_turns(prompt))
+            .stop([END_OF_TURN])
+            .cache_prompt(true)
+            .build();
+        request.temperature = preset.temperature;
+        request.top_p = preset.top_p;
+        request.top_k = preset.top_k;
+        request.seed = preset.seed;
+        request.n_predict = preset.num_predict;
+        request.repeat_penalty = preset.repeat_penalty;
+
+        async move {
+            let stream = client.completion(request).await?;
+            Ok(stream.map(|item| item.map(|chunk| chunk.content)).boxed())
+        }
+        .boxed()
+    }
+}
+
+const END_OF_TURN: &str = "<end_of_turn>";
+
+/// Wraps the prompt in Gemma's chat template, which `/completion` does not apply. The server
+/// adds the BOS token itself.
+fn gemma_turns(prompt: &Prompt) -> String {
+    format!("<start_of_turn>user\n{prompt}{END_OF_TURN}\n<start_of_turn>model\n")
+}
+
+/// A server of any supported kind.
+#[derive(Clone)]
+pub enum Backend {
+    Ollama(ollama::Client),
+    OpenAI(openai::Client),
+    LlamaCpp(llama_cpp::Client),
+    Fake(FakeBackend),
+}
+
+impl Backend {
+    pub fn new(kind: BackendKind, base_url: Url) -> Self {
+        match kind {
+            BackendKind::Ollama => Backend::Ollama(ollama::Client::new(base_url)),
+            BackendKind::OpenAI => Backend::OpenAI(openai::Client::new(base_url)),
+            BackendKind::LlamaCpp => Backend::LlamaCpp(llama_cpp::Client::new(base_url)),
+            BackendKind::Fake => Backend::Fake(FakeBackend::default()),
+        }
+    }
+
+    /// Creates the backend for a configured server.
+    pub fn from_config(config: &EndpointConfig) -> Result<Self, Error> {
+        let base_url = config.base_url.clone();
+        let options = &config.options;
+
+        Ok(match config.kind {
+            BackendKind::Ollama => {
+                Backend::Ollama(ollama::Client::with_options(base_url, options)?)
+            }
+            BackendKind::OpenAI => {
+                Backend::OpenAI(openai::Client::with_options(base_url, options)?)
+            }
+            BackendKind::LlamaCpp => {
+                Backend::LlamaCpp(llama_cpp::Client::with_options(base_url, options)?)
+            }
+            BackendKind::Fake => Backend::Fake(FakeBackend::new(config.fake.clone())),
+        })
+    }
+
+    pub fn kind(&self) -> BackendKind {
+        match self {
+            Backend::Ollama(_) => BackendKind::Ollama,
+            Backend::OpenAI(_) => BackendKind::OpenAI,
+            Backend::LlamaCpp(_) => BackendKind::LlamaCpp,
+            Backend::Fake(_) => BackendKind::Fake,
+        }
+    }
+
+    /// Returns the Ollama client, for operations that only Ollama offers.
+    pub fn ollama(&self) -> Option<&ollama::Client> {
+        match self {
+            Backend::Ollama(client) => Some(client),
+            Backend::OpenAI(_) | Backend::LlamaCpp(_) | Backend::Fake(_) => None,
+        }
+    }
+
+    pub fn ollama_mut(&mut self) -> Option<&mut ollama::Client> {
+        match self {
+            Backend::Ollama(client) => Some(client),
+            Backend::OpenAI(_) | Backend::LlamaCpp(_) | Backend::Fake(_) => None,
+        }
+    }
+}
+
+impl TranslationBackend for Backend {
+    fn list_models(&self) -> BoxFuture<'static, Result<Vec<AvailableModel>, Error>> {
+        match self {
+            Backend::Ollama(client) => client.list_models(),
+            Backend::OpenAI(client) => client.list_models(),
+            Backend::LlamaCpp(client) => client.list_models(),
+            Backend::Fake(backend) => backend.list_models(),
+        }
+    }
+
+    fn health(&self) -> BoxFuture<'static, Result<ServerInfo, Error>> {
+        match self {
+            Backend::Ollama(client) => client.health(),
+            Backend::OpenAI(client) => client.health(),
+            Backend::LlamaCpp(client) => client.health(),
+            Backend::Fake(backend) => backend.health(),
+        }
+    }
+
+    fn translate(
+        &self,
+        model: &str,
+        prompt: &Prompt,
+        preset: &ModelPreset,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String, Error>>, Error>> {
+        match self {
+            Backend::Ollama(client) => client.translate(model, prompt, preset),
+            Backend::OpenAI(client) => client.translate(model, prompt, preset),
+            Backend::LlamaCpp(client) => client.translate(model, prompt, preset),
+            Backend::Fake(backend) => backend.translate(model, prompt, preset),
+        }
+    }
+}
+
+/// A configured server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointConfig {
+    pub base_url: Url,
+    pub kind: BackendKind,
+    pub options: ConnectionOptions,
+    /// How the fake backend behaves, when it is selected.
+    pub fake: FakeOptions,
+}
+
+/// Translates with each backend in turn until one of them can be reached. Other errors, such
+/// as a missing model, are returned right away.
+pub async fn translate_with_failover<B: TranslationBackend>(
+    backends: &[B],
+    model: &str,
+    prompt: &Prompt,
+    preset: &ModelPreset,
+) -> Result<BoxStream<'static, Result<String, Error>>, Error> {
+    let mut last_error = None;
+
+    for backend in backends {
+        match backend.translate(model, prompt, preset).await {
+            Err(err) if err.is_unreachable() => last_error = Some(err),
+            result => return result,
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| Error::ModelNotFound(model.to_owned())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::Language;
+    use futures_util::stream;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A backend that fails with the given error or streams its name.
+    struct Stub {
+        name: &'static str,
+        error: Option<fn() -> Error>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl TranslationBackend for Stub {
+        fn list_models(&self) -> BoxFuture<'static, Result<Vec<AvailableModel>, Error>> {
+            async { Ok(Vec::new()) }.boxed()
+        }
+
+        fn health(&self) -> BoxFuture<'static, Result<ServerInfo, Error>> {
+            async { Ok(ServerInfo::default()) }.boxed()
+        }
+
+        fn translate(
+            &self,
+            _: &str,
+            _: &Prompt,
+            _: &ModelPreset,
+        ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String, Error>>, Error>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+
+            let result = match self.error {
+                Some(error) => Err(error()),
+                None => Ok(stream::iter([Ok(self.name.to_owned())]).boxed()),
+            };
+
+            async move { result }.boxed()
+        }
+    }
+
+    #[test]
+    fn test_gemma_turns() {
+        let prompt = Prompt::new(
+            Language::new("en", "English"),
+            Language::new("zh-Hans", "Chinese"),
+            "Hello, world!",
+        );
+
+        assert_eq!(
+            gemma_turns(&prompt),
+            format!("<start_of_turn>user\n{prompt}<end_of_turn>\n<start_of_turn>model\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_translate_with_failover() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let stub = |name, error| Stub {
+            name,
+            error,
+            calls: calls.clone(),
+        };
+        let unreachable = || Error::Connect(reqwest::Client::new().get("").build().unwrap_err());
+        let not_found = || Error::ModelNotFound("stub".to_owned());
+
+        let prompt = Prompt::new(
+            Language::new("en", "English"),
+            Language::new("zh-Hans", "Chinese"),
+            "Hello, world!",
+        );
+
+        let preset = ModelPreset::default();
+
+        let backends = [
+            stub("a", Some(unreachable)),
+            stub("b", None),
+            stub("c", None),
+        ];
+        let mut result = translate_with_failover(&backends, "stub", &prompt, &preset)
+            .await
+            .unwrap();
+        assert_eq!(result.next().await.unwrap().unwrap(), "b");
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 2);
+
+        // Only connection errors fail over.
+        let backends = [stub("a", Some(not_found)), stub("b", None)];
+        let err = translate_with_failover(&backends, "stub", &prompt, &preset)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ModelNotFound(_)), "{err:?}");
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 1);
+
+        let backends = [stub("a", Some(unreachable)), stub("b", Some(unreachable))];
+        let err = translate_with_failover(&backends, "stub", &prompt, &preset)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.is_unreachable(), "{err:?}");
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 2);
+
+        let err = translate_with_failover::<Stub>(&[], "stub", &prompt, &preset)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ModelNotFound(_)), "{err:?}");
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+/// Exponential backoff for retrying a request, doubling the delay after each failure.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Backoff {
+            initial,
+            max,
+            next: initial,
+        }
+    }
+
+    /// Returns the delay before the next retry.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next.min(self.max);
+        self.next = delay.saturating_mul(2);
+        delay
+    }
+
+    /// Starts over from the initial delay, e.g. after a request succeeds.
+    pub fn reset(&mut self) {
+        self.next = self.initial;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(10));
+
+        let delays = (0..6)
+            .map(|_| backoff.next_delay().as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 8, 10, 10]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+}
@@ -1,34 +1,120 @@
+use crate::backend::{BackendKind, EndpointConfig};
+use crate::fake::{self, FakeOptions};
+use crate::glossary::{Glossaries, Glossary};
+use crate::language::AUTO_DETECT;
+use crate::language_pair::{pair_key, LanguagePair, PairSettings, QuickPairs};
+use crate::masking::MaskingRules;
+use crate::model_filter::ModelFilter;
+use crate::model_preset::ModelPreset;
+use crate::ollama::{self, ConnectionOptions};
+use crate::prompt_template::PromptTemplate;
+use crate::secrets::Secrets;
+use crate::translation_style::TranslationStyle;
 use dirs::{document_dir, home_dir};
 use gpui::{Context, EventEmitter};
 use icu_locale::fallback::{LocaleFallbackConfig, LocaleFallbackPriority};
 use icu_locale::{locale, DataLocale, Locale, LocaleFallbacker};
 use rust_i18n::set_locale;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::mem;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+/// The default polling interval once connected to Ollama, in seconds.
+const DEFAULT_POLL_INTERVAL: u64 = 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(skip)]
     app_name: String,
+    /// Kept in a separate file, which may be shared within a team.
+    #[serde(skip)]
+    glossaries: Glossaries,
 
     locale: Option<String>,
     source_language: Option<String>,
     target_language: Option<String>,
 
     model: Option<String>,
+    /// Which installed models are listed in the Model menu.
+    #[serde(default)]
+    model_filter: ModelFilter,
+    /// Generation options for each model, keyed by model name.
+    #[serde(default)]
+    presets: BTreeMap<String, ModelPreset>,
+    /// Prompt templates by name, selectable for each language pair.
+    #[serde(default)]
+    prompt_templates: BTreeMap<String, String>,
+    /// Settings for each language pair, keyed by `source:target`.
+    #[serde(default)]
+    language_pairs: BTreeMap<String, PairSettings>,
+    /// Favorite and recently used language pairs, for switching between them quickly.
+    #[serde(default)]
+    quick_pairs: QuickPairs,
+    /// What is protected from translation, e.g. placeholders and markup.
+    #[serde(default)]
+    masking: MaskingRules,
     last_directory: Option<PathBuf>,
+
+    /// Overrides `OLLAMA_HOST` when set.
+    ollama_host: Option<String>,
+    /// More Ollama servers to use alongside the main one, e.g. other workstations.
+    #[serde(default)]
+    extra_ollama_hosts: Vec<String>,
+    /// Connection settings for each Ollama endpoint, keyed by base URL. Headers are kept in a
+    /// separate secrets file.
+    #[serde(default)]
+    connections: BTreeMap<String, ConnectionOptions>,
+    /// The API each endpoint speaks, keyed by base URL. Endpoints not listed are Ollama.
+    #[serde(default)]
+    backends: BTreeMap<String, BackendKind>,
+    /// How the fake backend behaves, for servers set to `fake`.
+    #[serde(default)]
+    fake: FakeOptions,
+    /// How often to poll Ollama once connected, in seconds.
+    poll_interval: Option<u64>,
+
+    /// Starts `ollama serve` when Ollama is not running.
+    #[serde(default)]
+    manage_ollama: bool,
+    /// The `ollama` binary to run, found on `PATH` when unset.
+    ollama_path: Option<PathBuf>,
+    /// Passed to `ollama serve` as `OLLAMA_MODELS`.
+    ollama_models_dir: Option<PathBuf>,
+
+    /// Loads the selected model into memory as soon as it is chosen.
+    #[serde(default)]
+    preload_model: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             app_name: env!("CARGO_PKG_NAME").to_string(),
+            glossaries: Glossaries::default(),
             locale: None,
-            source_language: Some("zh-Hans".to_owned()),
+            source_language: Some(AUTO_DETECT.code.to_owned()),
             target_language: Some("en".to_owned()),
             model: None,
+            model_filter: ModelFilter::default(),
+            presets: BTreeMap::new(),
+            prompt_templates: BTreeMap::new(),
+            language_pairs: BTreeMap::new(),
+            quick_pairs: QuickPairs::default(),
+            masking: MaskingRules::default(),
             last_directory: None,
+            ollama_host: None,
+            extra_ollama_hosts: Vec::new(),
+            connections: BTreeMap::new(),
+            backends: BTreeMap::new(),
+            fake: FakeOptions::default(),
+            poll_interval: None,
+            manage_ollama: false,
+            ollama_path: None,
+            ollama_models_dir: None,
+            preload_model: false,
         }
     }
 }
@@ -37,7 +123,7 @@ impl Config {
     pub fn load(app_name: impl Into<String>) -> Config {
         let app_name = app_name.into();
 
-        match confy::load::<Config>(&app_name, None) {
+        let mut config = match confy::load::<Config>(&app_name, None) {
             Ok(mut config) => {
                 config.app_name = app_name;
                 config
@@ -46,13 +132,96 @@ impl Config {
                 eprintln!("{err}");
                 Default::default()
             }
-        }
+        };
+
+        config.load_secrets();
+        config.load_glossaries();
+        config
     }
 
     pub fn store(&self) {
         if let Err(err) = confy::store(&self.app_name, None, self) {
             eprintln!("{err}")
         }
+
+        self.store_secrets();
+        self.store_glossaries();
+    }
+
+    /// Returns the path of a file next to the config file.
+    fn sibling_path(&self, file_name: &str) -> Option<PathBuf> {
+        confy::get_configuration_file_path(&self.app_name, None)
+            .map(|path| path.with_file_name(file_name))
+            .inspect_err(|err| eprintln!("{err}"))
+            .ok()
+    }
+
+    fn secrets_path(&self) -> Option<PathBuf> {
+        self.sibling_path("secrets.toml")
+    }
+
+    fn glossary_path(&self) -> Option<PathBuf> {
+        self.sibling_path("glossary.toml")
+    }
+
+    fn load_glossaries(&mut self) {
+        let Some(path) = self.glossary_path() else {
+            return;
+        };
+
+        match Glossaries::load(&path) {
+            Ok(glossaries) => self.glossaries = glossaries,
+            Err(err) => eprintln!("{}: {err}", path.display()),
+        }
+    }
+
+    fn store_glossaries(&self) {
+        let Some(path) = self.glossary_path() else {
+            return;
+        };
+
+        // Don't create the file until there is a glossary.
+        if self.glossaries.pairs.is_empty() && !path.exists() {
+            return;
+        }
+
+        if let Err(err) = self.glossaries.store(&path) {
+            eprintln!("{}: {err}", path.display())
+        }
+    }
+
+    fn load_secrets(&mut self) {
+        let Some(path) = self.secrets_path() else {
+            return;
+        };
+
+        match Secrets::load(&path) {
+            Ok(secrets) => {
+                for (endpoint, headers) in secrets.headers {
+                    self.connections.entry(endpoint).or_default().headers = headers;
+                }
+            }
+            Err(err) => eprintln!("{}: {err}", path.display()),
+        }
+    }
+
+    fn store_secrets(&self) {
+        let Some(path) = self.secrets_path() else {
+            return;
+        };
+
+        let secrets = Secrets {
+            headers: self
+                .connections
+                .iter()
+                .filter(|(_, options)| !options.headers.is_empty())
+                .map(|(endpoint, options)| (endpoint.clone(), options.headers.clone()))
+                .collect(),
+        };
+
+        if let Err(err) = secrets.store(&path) {
+            eprintln!("{}: {err}", path.display())
+        }
     }
 
     pub fn init(&mut self, cx: &mut Context<Self>) {
@@ -156,6 +325,34 @@ impl Config {
         });
     }
 
+    /// Switches to a favorite or recent language pair.
+    pub fn set_language_pair(&mut self, pair: &LanguagePair, cx: &mut Context<Self>) {
+        self.source_language = Some(pair.source.clone());
+        self.target_language = Some(pair.target.clone());
+
+        cx.emit(ConfigEvent::LanguagePairChange {
+            source_language: self.source_language.clone(),
+            target_language: self.target_language.clone(),
+        });
+    }
+
+    pub fn quick_pairs(&self) -> &QuickPairs {
+        &self.quick_pairs
+    }
+
+    /// Records a language pair as the latest one translated with.
+    pub fn record_language_pair(&mut self, pair: LanguagePair, cx: &mut Context<Self>) {
+        if self.quick_pairs.record(pair) {
+            cx.emit(ConfigEvent::QuickPairsChange);
+        }
+    }
+
+    pub fn toggle_favorite_pair(&mut self, pair: LanguagePair, cx: &mut Context<Self>) {
+        self.quick_pairs.toggle_favorite(pair);
+
+        cx.emit(ConfigEvent::QuickPairsChange);
+    }
+
     pub fn model(&self) -> Option<&String> {
         self.model.as_ref()
     }
@@ -166,6 +363,149 @@ impl Config {
         cx.emit(ConfigEvent::ModelChange);
     }
 
+    pub fn model_filter(&self) -> &ModelFilter {
+        &self.model_filter
+    }
+
+    pub fn set_model_filter(&mut self, model_filter: ModelFilter, cx: &mut Context<Self>) {
+        self.model_filter = model_filter;
+
+        cx.emit(ConfigEvent::ModelFilterChange);
+    }
+
+    pub fn toggle_show_all_models(&mut self, cx: &mut Context<Self>) {
+        self.model_filter.show_all = !self.model_filter.show_all;
+
+        cx.emit(ConfigEvent::ModelFilterChange);
+    }
+
+    /// Returns the generation options for a model.
+    pub fn preset(&self, model: &str) -> ModelPreset {
+        self.presets.get(model).cloned().unwrap_or_default()
+    }
+
+    pub fn set_preset(
+        &mut self,
+        model: impl Into<String>,
+        preset: ModelPreset,
+        cx: &mut Context<Self>,
+    ) {
+        let model = model.into();
+
+        if preset.is_empty() {
+            self.presets.remove(&model);
+        } else {
+            self.presets.insert(model, preset);
+        }
+
+        cx.emit(ConfigEvent::PresetChange);
+    }
+
+    /// Returns the saved prompt templates. Invalid ones, e.g. edited by hand, are reported and
+    /// skipped.
+    pub fn prompt_templates(&self) -> BTreeMap<String, PromptTemplate> {
+        self.prompt_templates
+            .iter()
+            .filter_map(|(name, template)| match template.parse() {
+                Ok(template) => Some((name.clone(), template)),
+                Err(err) => {
+                    eprintln!("invalid prompt template {name:?}: {err}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn pair_settings(&self, source: &str, target: &str) -> PairSettings {
+        self.language_pairs
+            .get(&pair_key(source, target))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the prompt template selected for a language pair, falling back to the built-in
+    /// one.
+    pub fn prompt_template(&self, source: &str, target: &str) -> PromptTemplate {
+        self.pair_settings(source, target)
+            .template
+            .and_then(|name| self.prompt_templates().remove(&name))
+            .unwrap_or_default()
+    }
+
+    /// Replaces the prompt templates and the settings of a language pair.
+    pub fn set_prompt_settings(
+        &mut self,
+        templates: BTreeMap<String, PromptTemplate>,
+        source: &str,
+        target: &str,
+        settings: PairSettings,
+        cx: &mut Context<Self>,
+    ) {
+        self.prompt_templates = templates
+            .into_iter()
+            .map(|(name, template)| (name, template.to_string()))
+            .collect();
+
+        self.update_pair_settings(source, target, |this| *this = settings);
+
+        cx.emit(ConfigEvent::PromptChange);
+    }
+
+    pub fn set_translation_style(
+        &mut self,
+        source: &str,
+        target: &str,
+        style: TranslationStyle,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_pair_settings(source, target, |this| this.style = style);
+
+        cx.emit(ConfigEvent::PromptChange);
+    }
+
+    /// Changes the settings of a language pair, dropping them once they are all unset.
+    fn update_pair_settings(
+        &mut self,
+        source: &str,
+        target: &str,
+        update: impl FnOnce(&mut PairSettings),
+    ) {
+        let key = pair_key(source, target);
+        let mut settings = self.language_pairs.remove(&key).unwrap_or_default();
+
+        update(&mut settings);
+
+        if !settings.is_empty() {
+            self.language_pairs.insert(key, settings);
+        }
+    }
+
+    pub fn masking(&self) -> &MaskingRules {
+        &self.masking
+    }
+
+    pub fn set_masking(&mut self, masking: MaskingRules, cx: &mut Context<Self>) {
+        self.masking = masking;
+
+        cx.emit(ConfigEvent::PromptChange);
+    }
+
+    pub fn glossary(&self, source: &str, target: &str) -> Glossary {
+        self.glossaries.get(source, target)
+    }
+
+    pub fn set_glossary(
+        &mut self,
+        source: &str,
+        target: &str,
+        glossary: Glossary,
+        cx: &mut Context<Self>,
+    ) {
+        self.glossaries.set(source, target, glossary);
+
+        cx.emit(ConfigEvent::PromptChange);
+    }
+
     pub fn last_directory(&self) -> Option<&PathBuf> {
         self.last_directory.as_ref()
     }
@@ -175,6 +515,151 @@ impl Config {
 
         cx.emit(ConfigEvent::LastDirectoryChange);
     }
+
+    pub fn ollama_host(&self) -> Option<&String> {
+        self.ollama_host.as_ref()
+    }
+
+    /// Returns the configured Ollama base URL, falling back to `OLLAMA_HOST` and then the default.
+    pub fn ollama_base_url(&self) -> Url {
+        self.ollama_host
+            .as_ref()
+            .and_then(|host| ollama::parse_host(host).ok())
+            .unwrap_or_else(ollama::default_base_url)
+    }
+
+    pub fn extra_ollama_hosts(&self) -> &[String] {
+        &self.extra_ollama_hosts
+    }
+
+    /// Returns the base URLs of all configured Ollama servers, the main one first.
+    pub fn ollama_base_urls(&self) -> Vec<Url> {
+        let mut base_urls = vec![self.ollama_base_url()];
+
+        for host in &self.extra_ollama_hosts {
+            match ollama::parse_host(host) {
+                Ok(base_url) if !base_urls.contains(&base_url) => base_urls.push(base_url),
+                Ok(_) => {}
+                Err(err) => eprintln!("{host}: {err}"),
+            }
+        }
+
+        base_urls
+    }
+
+    /// Returns every configured server with its backend and connection settings. All of them
+    /// use the fake backend when `TRANSLATE_GEMMA_FAKE` is set.
+    pub fn endpoints(&self) -> Vec<EndpointConfig> {
+        let forced_fake = fake::is_forced();
+
+        self.ollama_base_urls()
+            .into_iter()
+            .map(|base_url| EndpointConfig {
+                kind: if forced_fake {
+                    BackendKind::Fake
+                } else {
+                    self.backend_kind(&base_url)
+                },
+                options: self.connection_options(&base_url),
+                fake: self.fake.clone(),
+                base_url,
+            })
+            .collect()
+    }
+
+    /// Returns the API a server speaks.
+    pub fn backend_kind(&self, base_url: &Url) -> BackendKind {
+        self.backends
+            .get(base_url.as_str())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the connection settings for a server.
+    pub fn connection_options(&self, base_url: &Url) -> ConnectionOptions {
+        self.connections
+            .get(base_url.as_str())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_endpoint_settings(
+        &mut self,
+        base_url: &Url,
+        kind: BackendKind,
+        options: ConnectionOptions,
+        cx: &mut Context<Self>,
+    ) {
+        let endpoint = base_url.to_string();
+
+        if kind == BackendKind::default() {
+            self.backends.remove(&endpoint);
+        } else {
+            self.backends.insert(endpoint.clone(), kind);
+        }
+
+        if options == ConnectionOptions::default() {
+            self.connections.remove(&endpoint);
+        } else {
+            self.connections.insert(endpoint, options);
+        }
+
+        cx.emit(ConfigEvent::ConnectionOptionsChange);
+    }
+
+    pub fn set_ollama_hosts(
+        &mut self,
+        host: Option<String>,
+        extra_hosts: Vec<String>,
+        cx: &mut Context<Self>,
+    ) {
+        self.ollama_host = host.filter(|host| !host.trim().is_empty());
+        self.extra_ollama_hosts = extra_hosts
+            .into_iter()
+            .map(|host| host.trim().to_owned())
+            .filter(|host| !host.is_empty())
+            .collect();
+
+        cx.emit(ConfigEvent::OllamaHostChange);
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL).max(1))
+    }
+
+    pub fn set_poll_interval(&mut self, poll_interval: Duration, cx: &mut Context<Self>) {
+        self.poll_interval = Some(poll_interval.as_secs());
+
+        cx.emit(ConfigEvent::PollIntervalChange);
+    }
+
+    pub fn manage_ollama(&self) -> bool {
+        self.manage_ollama
+    }
+
+    pub fn toggle_manage_ollama(&mut self, cx: &mut Context<Self>) {
+        self.manage_ollama = !self.manage_ollama;
+
+        cx.emit(ConfigEvent::ManageOllamaChange);
+    }
+
+    pub fn ollama_path(&self) -> Option<&PathBuf> {
+        self.ollama_path.as_ref()
+    }
+
+    pub fn ollama_models_dir(&self) -> Option<&PathBuf> {
+        self.ollama_models_dir.as_ref()
+    }
+
+    pub fn preload_model(&self) -> bool {
+        self.preload_model
+    }
+
+    pub fn toggle_preload_model(&mut self, cx: &mut Context<Self>) {
+        self.preload_model = !self.preload_model;
+
+        cx.emit(ConfigEvent::PreloadModelChange);
+    }
 }
 
 pub enum ConfigEvent {
@@ -185,8 +670,21 @@ pub enum ConfigEvent {
         source_language: Option<String>,
         target_language: Option<String>,
     },
+    LanguagePairChange {
+        source_language: Option<String>,
+        target_language: Option<String>,
+    },
+    QuickPairsChange,
     ModelChange,
+    ModelFilterChange,
+    PresetChange,
+    PromptChange,
     LastDirectoryChange,
+    OllamaHostChange,
+    ConnectionOptionsChange,
+    PollIntervalChange,
+    ManageOllamaChange,
+    PreloadModelChange,
 }
 
 impl EventEmitter<ConfigEvent> for Config {}
@@ -0,0 +1,264 @@
+use crate::backend::BackendKind;
+use crate::config::Config;
+use crate::ollama::ConnectionOptions;
+use gpui::{div, prelude::*, App, Entity, SharedString, Window};
+use gpui_component::checkbox::Checkbox;
+use gpui_component::input::{Input, InputState};
+use gpui_component::label::Label;
+use gpui_component::notification::Notification;
+use gpui_component::radio::RadioGroup;
+use gpui_component::{ActiveTheme, WindowExt};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use url::Url;
+
+/// Parses headers written as `Name: value`, one per line.
+fn parse_headers(text: &str) -> Result<BTreeMap<String, String>, String> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(format!(
+                    "{}: {line:?}",
+                    t!("connection-settings.invalid-header")
+                ));
+            };
+
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn parse_timeout(text: &str) -> Result<Option<u64>, String> {
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    text.parse()
+        .map(Some)
+        .map_err(|_| format!("{}: {text:?}", t!("connection-settings.invalid-timeout")))
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+pub struct ConnectionSettings {
+    kind: BackendKind,
+    headers: Entity<InputState>,
+    ca_bundle: Entity<InputState>,
+    proxy: Entity<InputState>,
+    connect_timeout: Entity<InputState>,
+    read_timeout: Entity<InputState>,
+    accept_invalid_certs: bool,
+}
+
+impl ConnectionSettings {
+    pub fn new(
+        kind: BackendKind,
+        options: ConnectionOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let headers = options
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let input =
+            |placeholder: &str, value: String, window: &mut Window, cx: &mut Context<Self>| {
+                cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .placeholder(placeholder.to_string())
+                        .default_value(value)
+                })
+            };
+
+        ConnectionSettings {
+            kind,
+            headers: cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .rows(3)
+                    .placeholder("Authorization: Bearer ...")
+                    .default_value(headers)
+            }),
+            ca_bundle: input(
+                "/path/to/ca.pem",
+                options
+                    .ca_bundle
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+                window,
+                cx,
+            ),
+            proxy: input(
+                "http://proxy.internal:3128",
+                options.proxy.unwrap_or_default(),
+                window,
+                cx,
+            ),
+            connect_timeout: input(
+                "10",
+                options
+                    .connect_timeout
+                    .map(|secs| secs.to_string())
+                    .unwrap_or_default(),
+                window,
+                cx,
+            ),
+            read_timeout: input(
+                "300",
+                options
+                    .read_timeout
+                    .map(|secs| secs.to_string())
+                    .unwrap_or_default(),
+                window,
+                cx,
+            ),
+            accept_invalid_certs: options.accept_invalid_certs,
+        }
+    }
+
+    pub fn kind(&self) -> BackendKind {
+        self.kind
+    }
+
+    /// Returns the options as entered, or a message describing the first invalid field.
+    pub fn options(&self, cx: &App) -> Result<ConnectionOptions, String> {
+        let options = ConnectionOptions {
+            headers: parse_headers(&self.headers.read(cx).value())?,
+            ca_bundle: non_empty(&self.ca_bundle.read(cx).value()).map(PathBuf::from),
+            accept_invalid_certs: self.accept_invalid_certs,
+            proxy: non_empty(&self.proxy.read(cx).value()),
+            connect_timeout: parse_timeout(&self.connect_timeout.read(cx).value())?,
+            read_timeout: parse_timeout(&self.read_timeout.read(cx).value())?,
+        };
+
+        // Catches malformed headers, unreadable certificates and invalid proxy URLs.
+        options.build_client().map_err(|err| err.to_string())?;
+
+        Ok(options)
+    }
+}
+
+fn field(label: impl Into<SharedString>, input: &Entity<InputState>) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(Label::new(label).text_sm())
+        .child(Input::new(input))
+}
+
+impl Render for ConnectionSettings {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(Label::new(t!("connection-settings.backend")).text_sm())
+                    .child(
+                        RadioGroup::horizontal("backend")
+                            .children(BackendKind::ALL.map(BackendKind::name))
+                            .selected_index(
+                                BackendKind::ALL.iter().position(|kind| *kind == self.kind),
+                            )
+                            .on_click(cx.listener(|this, index: &usize, _, cx| {
+                                this.kind = BackendKind::ALL[*index];
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(field(t!("connection-settings.headers"), &self.headers))
+            .child(
+                Label::new(t!("connection-settings.headers.description"))
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(field(t!("connection-settings.ca-bundle"), &self.ca_bundle))
+            .child(
+                Checkbox::new("accept-invalid-certs")
+                    .label(t!("connection-settings.accept-invalid-certs"))
+                    .checked(self.accept_invalid_certs)
+                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                        this.accept_invalid_certs = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(field(t!("connection-settings.proxy"), &self.proxy))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(div().flex_1().child(field(
+                        t!("connection-settings.connect-timeout"),
+                        &self.connect_timeout,
+                    )))
+                    .child(div().flex_1().child(field(
+                        t!("connection-settings.read-timeout"),
+                        &self.read_timeout,
+                    ))),
+            )
+    }
+}
+
+pub fn open_connection_settings_dialog(
+    config: Entity<Config>,
+    base_url: Url,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let kind = config.read(cx).backend_kind(&base_url);
+    let options = config.read(cx).connection_options(&base_url);
+    let view = cx.new(|cx| ConnectionSettings::new(kind, options, window, cx));
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let view = view.clone();
+        let config = config.clone();
+        let base_url = base_url.clone();
+
+        dialog
+            .confirm()
+            .title(t!("connection-settings").to_string())
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        Label::new(base_url.to_string())
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(view.clone()),
+            )
+            .on_ok(move |_, window, cx| {
+                let options = match view.read(cx).options(cx) {
+                    Ok(options) => options,
+                    Err(err) => {
+                        window.push_notification(Notification::error(err), cx);
+                        return false;
+                    }
+                };
+
+                let kind = view.read(cx).kind();
+
+                config.update(cx, |this, cx| {
+                    this.set_endpoint_settings(&base_url, kind, options, cx);
+                });
+
+                true
+            })
+    });
+}
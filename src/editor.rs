@@ -1,5 +1,6 @@
+use crate::language::Direction;
 use gpui::{
-    div, prelude::*, transparent_white, white, App, ClipboardItem, ElementId, Entity,
+    div, prelude::*, transparent_white, white, AnyElement, App, ClipboardItem, ElementId, Entity,
     FocusHandle, Focusable, IntoElement, SharedString, Window,
 };
 use gpui_component::button::{Button, ButtonVariants};
@@ -33,6 +34,8 @@ impl InputStateEntityExt for Entity<InputState> {
 pub struct Editor {
     id: ElementId,
     state: Entity<InputState>,
+    banners: Vec<AnyElement>,
+    direction: Direction,
 }
 
 impl Focusable for Editor {
@@ -46,8 +49,23 @@ impl Editor {
         Editor {
             id: id.into(),
             state: state.clone(),
+            banners: Vec::new(),
+            direction: Direction::default(),
         }
     }
+
+    /// Aligns the text to the right for languages written from right to left, e.g. Arabic.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Shows an element above the text, e.g. an error message. Banners are stacked in the order
+    /// they are added.
+    pub fn banner(mut self, banner: impl IntoElement) -> Self {
+        self.banners.push(banner.into_any_element());
+        self
+    }
 }
 
 impl RenderOnce for Editor {
@@ -67,11 +85,15 @@ impl RenderOnce for Editor {
             .when(self.focus_handle(cx).is_focused(window), |this| {
                 this.shadow_sm().border_1().border_color(gray_300())
             })
+            .children(self.banners)
             .child(
                 Input::new(&self.state)
                     .size_full()
                     .bordered(false)
-                    .rounded_xl(),
+                    .rounded_xl()
+                    .when(self.direction == Direction::RightToLeft, |this| {
+                        this.text_right()
+                    }),
             )
             .child(
                 div()
@@ -1,3 +1,4 @@
+use crate::ollama;
 use gpui::{div, prelude::*, App, IntoElement, SharedString, Window};
 use gpui_component::description_list::DescriptionList;
 use gpui_component::label::Label;
@@ -86,3 +87,31 @@ pub fn show_io_error(
         cx,
     );
 }
+
+/// Returns a localized summary of an Ollama error and a suggested fix.
+pub fn describe_ollama_error(error: &ollama::Error) -> (SharedString, SharedString) {
+    let (summary, fix) = match error {
+        ollama::Error::Connect(_) => (t!("error.ollama.connect"), t!("error.ollama.connect.fix")),
+        ollama::Error::Timeout(_) => (t!("error.ollama.timeout"), t!("error.ollama.timeout.fix")),
+        ollama::Error::ModelNotFound(model) => (
+            t!("error.ollama.model-not-found", model = model),
+            t!("error.ollama.model-not-found.fix", model = model),
+        ),
+        ollama::Error::Status { .. } | ollama::Error::Stream(_) => {
+            (t!("error.ollama.server"), t!("error.ollama.server.fix"))
+        }
+        ollama::Error::Decode(_) | ollama::Error::Version(_) => {
+            (t!("error.ollama.decode"), t!("error.ollama.decode.fix"))
+        }
+        ollama::Error::Request(_) | ollama::Error::Task(_) => {
+            (t!("error.ollama.request"), t!("error.ollama.request.fix"))
+        }
+        ollama::Error::Options(_) => (t!("error.ollama.options"), t!("error.ollama.options.fix")),
+        ollama::Error::Unsupported(_) => (
+            t!("error.ollama.unsupported"),
+            t!("error.ollama.unsupported.fix"),
+        ),
+    };
+
+    (summary.into(), fix.into())
+}
@@ -0,0 +1,265 @@
+use crate::backend::{AvailableModel, ServerInfo, TranslationBackend};
+use crate::http::{AbortOnDrop, RUNTIME};
+use crate::model_preset::ModelPreset;
+use crate::ollama::{self, Error};
+use crate::prompt::Prompt;
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+use futures_util::{stream, FutureExt, StreamExt};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Uses the fake backend for every server when set to anything but `0`, e.g.
+/// `TRANSLATE_GEMMA_FAKE=1`.
+pub const FAKE_ENV: &str = "TRANSLATE_GEMMA_FAKE";
+
+/// The models the fake server lists, with their sizes.
+const MODELS: &[(&str, u64)] = &[
+    ("translategemma:4b-fake", 3_338_801_804),
+    ("translategemma:12b-fake", 8_149_190_253),
+];
+
+/// Returns `true` if the fake backend is selected with [`FAKE_ENV`].
+pub fn is_forced() -> bool {
+    env::var_os(FAKE_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FakeError {
+    /// Fails every request with `503 Service Unavailable`.
+    Unavailable,
+    /// Fails translations as if the model had not been pulled.
+    ModelNotFound,
+    /// Fails translations partway through the stream.
+    Stream,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FakeOptions {
+    /// The delay before each chunk, in milliseconds.
+    pub latency: u64,
+    /// How many words each chunk holds.
+    pub chunk_words: usize,
+    pub error: Option<FakeError>,
+    /// How many chunks to stream before failing with a stream error.
+    pub error_after: usize,
+}
+
+impl Default for FakeOptions {
+    fn default() -> Self {
+        FakeOptions {
+            latency: 30,
+            chunk_words: 1,
+            error: None,
+            error_after: 3,
+        }
+    }
+}
+
+/// A backend that answers without a server or model, for demos and tests. The translation is
+/// the text with the words of each line reversed, tagged with the target language.
+#[derive(Clone, Default)]
+pub struct FakeBackend {
+    options: FakeOptions,
+}
+
+impl FakeBackend {
+    pub fn new(options: FakeOptions) -> Self {
+        FakeBackend { options }
+    }
+
+    fn unavailable(&self) -> Result<(), Error> {
+        match self.options.error {
+            Some(FakeError::Unavailable) => Err(Error::Status {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                message: "fake server unavailable".to_owned(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the fake translation of the prompt, e.g. `[zh-Hans] world! Hello,`.
+pub fn translate_text(prompt: &Prompt) -> String {
+    let lines = prompt
+        .text
+        .lines()
+        .map(|line| line.split_whitespace().rev().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("[{}] {lines}", prompt.target_language.code)
+}
+
+/// Splits the text into chunks of the given number of words, each with the whitespace that
+/// follows it.
+fn chunks(text: &str, chunk_words: usize) -> Vec<String> {
+    text.split_inclusive(char::is_whitespace)
+        .collect::<Vec<_>>()
+        .chunks(chunk_words.max(1))
+        .map(|words| words.concat())
+        .collect()
+}
+
+impl TranslationBackend for FakeBackend {
+    fn list_models(&self) -> BoxFuture<'static, Result<Vec<AvailableModel>, Error>> {
+        let result = self.unavailable().map(|_| {
+            MODELS
+                .iter()
+                .map(|(name, size)| AvailableModel {
+                    name: name.to_string(),
+                    size: Some(*size),
+                })
+                .collect()
+        });
+
+        async move { result }.boxed()
+    }
+
+    /// Reports the recommended Ollama version, so that no feature is degraded.
+    fn health(&self) -> BoxFuture<'static, Result<ServerInfo, Error>> {
+        let result = self.unavailable().map(|_| ServerInfo {
+            version: Some(ollama::recommended_version()),
+            ..Default::default()
+        });
+
+        async move { result }.boxed()
+    }
+
+    /// Streams the fake translation on the runtime, so that the latency does not depend on the
+    /// executor polling the stream. The preset is ignored.
+    fn translate(
+        &self,
+        model: &str,
+        prompt: &Prompt,
+        _: &ModelPreset,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String, Error>>, Error>> {
+        let FakeOptions {
+            latency,
+            chunk_words,
+            error,
+            error_after,
+        } = self.options.clone();
+        let unavailable = self.unavailable();
+        let model = model.to_owned();
+        let chunks = chunks(&translate_text(prompt), chunk_words);
+
+        async move {
+            unavailable?;
+
+            if error == Some(FakeError::ModelNotFound) {
+                return Err(Error::ModelNotFound(model));
+            }
+
+            let (tx, mut rx) = mpsc::channel(32);
+
+            let task = AbortOnDrop(RUNTIME.spawn(async move {
+                for (index, chunk) in chunks.into_iter().enumerate() {
+                    tokio::time::sleep(Duration::from_millis(latency)).await;
+
+                    let item = if error == Some(FakeError::Stream) && index == error_after {
+                        Err(Error::Stream("fake stream error".to_owned()))
+                    } else {
+                        Ok(chunk)
+                    };
+                    let failed = item.is_err();
+
+                    if tx.send(item).await.is_err() || failed {
+                        break;
+                    }
+                }
+            }));
+
+            Ok(stream::poll_fn(move |cx| {
+                let _task = &task;
+                rx.poll_recv(cx)
+            })
+            .boxed())
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::Language;
+
+    fn prompt() -> Prompt {
+        Prompt::new(
+            Language::new("en", "English"),
+            Language::new("zh-Hans", "Chinese"),
+            "Hello, world!\nHow are you?",
+        )
+    }
+
+    async fn translate(options: FakeOptions) -> Result<Vec<Result<String, Error>>, Error> {
+        let stream = FakeBackend::new(options)
+            .translate("translategemma", &prompt(), &ModelPreset::default())
+            .await?;
+
+        Ok(stream.collect().await)
+    }
+
+    #[test]
+    fn test_translate_text() {
+        assert_eq!(
+            translate_text(&prompt()),
+            "[zh-Hans] world! Hello,\nyou? are How"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fake_backend() {
+        let backend = FakeBackend::default();
+
+        let models = backend.list_models().await.unwrap();
+        assert_eq!(models.len(), MODELS.len());
+
+        let info = backend.health().await.unwrap();
+        assert_eq!(info.version, Some(ollama::recommended_version()));
+
+        let chunks = translate(FakeOptions {
+            latency: 0,
+            chunk_words: 2,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        assert_eq!(chunks, ["[zh-Hans] world! ", "Hello,\nyou? ", "are How"]);
+    }
+
+    #[tokio::test]
+    async fn test_fake_errors() {
+        let options = |error| FakeOptions {
+            latency: 0,
+            error: Some(error),
+            error_after: 2,
+            ..Default::default()
+        };
+
+        let backend = FakeBackend::new(options(FakeError::Unavailable));
+        let err = backend.health().await.unwrap_err();
+        assert!(matches!(err, Error::Status { status, .. } if status == 503));
+        assert!(backend.list_models().await.is_err());
+
+        let err = translate(options(FakeError::ModelNotFound))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ModelNotFound(model) if model == "translategemma"));
+
+        let items = translate(options(FakeError::Stream)).await.unwrap();
+        assert_eq!(items.len(), 3);
+        assert!(items[..2].iter().all(Result::is_ok));
+        assert!(matches!(&items[2], Err(Error::Stream(_))));
+    }
+}
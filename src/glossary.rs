@@ -0,0 +1,565 @@
+use crate::language_pair::pair_key;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// A term and the translation it must have.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    pub source: String,
+    /// Unused for terms that are not translated.
+    #[serde(default)]
+    pub target: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub case_sensitive: bool,
+    /// Keeps the term as written, e.g. a product name.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub do_not_translate: bool,
+}
+
+impl GlossaryEntry {
+    pub fn new(source: impl Into<String>, target: impl Into<String>) -> Self {
+        GlossaryEntry {
+            source: source.into(),
+            target: target.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the text the translation must contain.
+    pub fn translation(&self) -> &str {
+        if self.do_not_translate {
+            &self.source
+        } else {
+            &self.target
+        }
+    }
+}
+
+/// Builds a regex that finds the term as a whole word. Scripts written without spaces, such as
+/// Chinese and Japanese, match anywhere.
+fn term_regex(term: &str, case_sensitive: bool) -> Option<Regex> {
+    let term = term.trim();
+
+    if term.is_empty() {
+        return None;
+    }
+
+    let boundary = |c: Option<char>| match c {
+        Some(c) if c.is_alphanumeric() && c < '\u{2E80}' => r"\b",
+        _ => "",
+    };
+
+    let start = boundary(term.chars().next());
+    let end = boundary(term.chars().last());
+
+    RegexBuilder::new(&format!("{start}{}{end}", regex::escape(term)))
+        .case_insensitive(!case_sensitive)
+        .build()
+        .ok()
+}
+
+fn contains_term(text: &str, term: &str, case_sensitive: bool) -> bool {
+    term_regex(term, case_sensitive).is_some_and(|regex| regex.is_match(text))
+}
+
+/// The terms of one language pair.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Glossary {
+    pub entries: Vec<GlossaryEntry>,
+}
+
+impl Glossary {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries whose term appears in the text.
+    pub fn matches(&self, text: &str) -> Vec<GlossaryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| contains_term(text, &entry.source, entry.case_sensitive))
+            .cloned()
+            .collect()
+    }
+
+    /// Adds the entries, replacing those with the same term.
+    pub fn merge(&mut self, other: Glossary) {
+        for entry in other.entries {
+            match self
+                .entries
+                .iter_mut()
+                .find(|existing| existing.source == entry.source)
+            {
+                Some(existing) => *existing = entry,
+                None => self.entries.push(entry),
+            }
+        }
+    }
+}
+
+/// Returns the instructions for the matched entries, filled into `{glossary}`. Empty when no
+/// entry matched.
+pub fn glossary_instructions(entries: &[GlossaryEntry]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+
+    let mut instructions =
+        "Translate the following terms exactly as given, keeping the terms marked as such unchanged:"
+            .to_owned();
+
+    for entry in entries {
+        if entry.do_not_translate {
+            write!(instructions, "\n- {} (keep unchanged)", entry.source).unwrap();
+        } else {
+            write!(instructions, "\n- {} → {}", entry.source, entry.target).unwrap();
+        }
+    }
+
+    instructions
+}
+
+/// The longest input sentence shown for a missing term, in characters.
+const MAX_SENTENCE_CHARS: usize = 100;
+
+/// A glossary entry whose translation is missing from the output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingTerm {
+    pub entry: GlossaryEntry,
+    /// The sentence of the input the term appears in, shortened if it is long.
+    pub sentence: Option<String>,
+}
+
+/// Returns the sentence of the text the term first appears in.
+fn find_sentence(text: &str, term: &str, case_sensitive: bool) -> Option<String> {
+    let regex = term_regex(term, case_sensitive)?;
+    let sentence = text
+        .unicode_sentences()
+        .find(|sentence| regex.is_match(sentence))?
+        .trim();
+
+    if sentence.chars().count() > MAX_SENTENCE_CHARS {
+        let shortened = sentence
+            .chars()
+            .take(MAX_SENTENCE_CHARS)
+            .collect::<String>();
+        Some(format!("{}…", shortened.trim_end()))
+    } else {
+        Some(sentence.to_owned())
+    }
+}
+
+/// Returns the entries whose translation is missing from the output, each with the sentence of
+/// the input its term appears in.
+pub fn check_output(entries: &[GlossaryEntry], input: &str, output: &str) -> Vec<MissingTerm> {
+    entries
+        .iter()
+        .filter(|entry| !entry.translation().trim().is_empty())
+        .filter(|entry| !contains_term(output, entry.translation(), entry.case_sensitive))
+        .map(|entry| MissingTerm {
+            entry: entry.clone(),
+            sentence: find_sentence(input, &entry.source, entry.case_sensitive),
+        })
+        .collect()
+}
+
+fn parse_bool(text: &str) -> bool {
+    matches!(
+        text.trim().to_lowercase().as_str(),
+        "true" | "yes" | "y" | "1" | "x"
+    )
+}
+
+impl Glossary {
+    /// Reads a CSV file with a header row. The `source` and `target` columns are required,
+    /// `case_sensitive` and `do_not_translate` are optional.
+    pub fn from_csv(text: &str) -> io::Result<Glossary> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+
+        let headers = reader.headers().map_err(io::Error::other)?.clone();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header.eq_ignore_ascii_case(name))
+        };
+
+        let (Some(source), Some(target)) = (column("source"), column("target")) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "missing source or target column",
+            ));
+        };
+        let case_sensitive = column("case_sensitive");
+        let do_not_translate = column("do_not_translate");
+
+        let mut entries = Vec::new();
+
+        for record in reader.records() {
+            let record = record.map_err(io::Error::other)?;
+            let field = |index: Option<usize>| index.and_then(|index| record.get(index));
+
+            let Some(source) = field(Some(source)).filter(|source| !source.is_empty()) else {
+                continue;
+            };
+
+            entries.push(GlossaryEntry {
+                source: source.to_owned(),
+                target: field(Some(target)).unwrap_or_default().to_owned(),
+                case_sensitive: field(case_sensitive).is_some_and(parse_bool),
+                do_not_translate: field(do_not_translate).is_some_and(parse_bool),
+            });
+        }
+
+        Ok(Glossary { entries })
+    }
+
+    pub fn to_csv(&self) -> io::Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        writer
+            .write_record(["source", "target", "case_sensitive", "do_not_translate"])
+            .map_err(io::Error::other)?;
+
+        for entry in &self.entries {
+            writer
+                .write_record([
+                    entry.source.clone(),
+                    entry.target.clone(),
+                    entry.case_sensitive.to_string(),
+                    entry.do_not_translate.to_string(),
+                ])
+                .map_err(io::Error::other)?;
+        }
+
+        let bytes = writer.into_inner().map_err(io::Error::other)?;
+        String::from_utf8(bytes).map_err(io::Error::other)
+    }
+}
+
+/// The `descrip` types used for the flags that TBX has no standard field for.
+const TBX_CASE_SENSITIVE: &str = "x-caseSensitive";
+const TBX_DO_NOT_TRANSLATE: &str = "x-doNotTranslate";
+
+/// Returns `true` if a TBX language tag is the language code or a variant of it, e.g. `en-US`
+/// for `en`.
+fn lang_matches(lang: &str, code: &str) -> bool {
+    let primary = |tag: &str| {
+        tag.split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+
+    lang.eq_ignore_ascii_case(code) || primary(lang) == primary(code)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Glossary {
+    /// Reads the terms of a language pair from a TBX file. Both TBX v3 (`conceptEntry`,
+    /// `langSec`) and the older TBX 2008 (`termEntry`, `langSet`) layouts are read. Concepts
+    /// without a term in the target language are kept unchanged.
+    pub fn from_tbx(text: &str, source: &str, target: &str) -> io::Result<Glossary> {
+        let document = roxmltree::Document::parse(text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let lang = |node: roxmltree::Node| {
+            node.attribute(("http://www.w3.org/XML/1998/namespace", "lang"))
+                .unwrap_or_default()
+                .to_owned()
+        };
+        let term = |node: roxmltree::Node| {
+            node.descendants()
+                .find(|node| node.has_tag_name("term"))
+                .and_then(|node| node.text())
+                .map(|text| text.trim().to_owned())
+                .filter(|text| !text.is_empty())
+        };
+        let note = |node: roxmltree::Node, kind: &str| {
+            node.descendants()
+                .filter(|node| node.has_tag_name("termNote") || node.has_tag_name("descrip"))
+                .any(|node| {
+                    node.attribute("type") == Some(kind) && node.text().is_some_and(parse_bool)
+                })
+        };
+
+        let mut entries = Vec::new();
+
+        for concept in document
+            .descendants()
+            .filter(|node| node.has_tag_name("conceptEntry") || node.has_tag_name("termEntry"))
+        {
+            let sections = concept
+                .children()
+                .filter(|node| node.has_tag_name("langSec") || node.has_tag_name("langSet"))
+                .collect::<Vec<_>>();
+
+            let find = |code: &str| {
+                sections
+                    .iter()
+                    .find(|section| lang(**section).eq_ignore_ascii_case(code))
+                    .or_else(|| {
+                        sections
+                            .iter()
+                            .find(|section| lang_matches(&lang(**section), code))
+                    })
+                    .copied()
+            };
+
+            let Some(source_section) = find(source) else {
+                continue;
+            };
+            let Some(source_term) = term(source_section) else {
+                continue;
+            };
+            let target_term = find(target).and_then(term);
+
+            entries.push(GlossaryEntry {
+                do_not_translate: target_term.is_none() || note(concept, TBX_DO_NOT_TRANSLATE),
+                case_sensitive: note(concept, TBX_CASE_SENSITIVE),
+                target: target_term.unwrap_or_default(),
+                source: source_term,
+            });
+        }
+
+        Ok(Glossary { entries })
+    }
+
+    /// Writes the terms as a TBX v3 (TBX-Basic) file.
+    pub fn to_tbx(&self, source: &str, target: &str) -> String {
+        let mut tbx = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <tbx type=\"TBX-Basic\" style=\"dca\" xml:lang=\"{}\" xmlns=\"urn:iso:std:iso:30042:ed-2\">\n\
+             \x20 <tbxHeader>\n\
+             \x20   <fileDesc><sourceDesc><p>TranslateGemma Desktop</p></sourceDesc></fileDesc>\n\
+             \x20 </tbxHeader>\n\
+             \x20 <text>\n\
+             \x20   <body>\n",
+            escape_xml(source)
+        );
+
+        let lang_sec = |lang: &str, term: &str| {
+            format!(
+                "        <langSec xml:lang=\"{}\"><termSec><term>{}</term></termSec></langSec>\n",
+                escape_xml(lang),
+                escape_xml(term)
+            )
+        };
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            writeln!(tbx, "      <conceptEntry id=\"c{}\">", index + 1).unwrap();
+
+            for (kind, value) in [
+                (TBX_CASE_SENSITIVE, entry.case_sensitive),
+                (TBX_DO_NOT_TRANSLATE, entry.do_not_translate),
+            ] {
+                if value {
+                    writeln!(tbx, "        <descrip type=\"{kind}\">true</descrip>").unwrap();
+                }
+            }
+
+            tbx.push_str(&lang_sec(source, &entry.source));
+            tbx.push_str(&lang_sec(target, entry.translation()));
+            tbx.push_str("      </conceptEntry>\n");
+        }
+
+        tbx.push_str("    </body>\n  </text>\n</tbx>\n");
+        tbx
+    }
+}
+
+/// The glossaries of all language pairs, kept in `glossary.toml` next to the config file.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Glossaries {
+    /// Keyed by `source:target`.
+    pub pairs: BTreeMap<String, Glossary>,
+}
+
+impl Glossaries {
+    /// Loads the glossaries, treating a missing file as empty.
+    pub fn load(path: &Path) -> io::Result<Glossaries> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Glossaries::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn store(&self, path: &Path) -> io::Result<()> {
+        let text = toml::to_string(self).map_err(io::Error::other)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, text)
+    }
+
+    pub fn get(&self, source: &str, target: &str) -> Glossary {
+        self.pairs
+            .get(&pair_key(source, target))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, source: &str, target: &str, glossary: Glossary) {
+        let key = pair_key(source, target);
+
+        if glossary.is_empty() {
+            self.pairs.remove(&key);
+        } else {
+            self.pairs.insert(key, glossary);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary() -> Glossary {
+        Glossary {
+            entries: vec![
+                GlossaryEntry {
+                    do_not_translate: true,
+                    ..GlossaryEntry::new("Acme Cloud", "")
+                },
+                GlossaryEntry::new("invoice", "Rechnung"),
+                GlossaryEntry {
+                    case_sensitive: true,
+                    ..GlossaryEntry::new("Go", "Go")
+                },
+                GlossaryEntry::new("世界", "world"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let glossary = glossary();
+
+        let terms = |text| {
+            glossary
+                .matches(text)
+                .into_iter()
+                .map(|entry| entry.source)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            terms("Send the Invoice from ACME CLOUD."),
+            ["Acme Cloud", "invoice"]
+        );
+        assert_eq!(terms("invoices, go"), Vec::<String>::new());
+        assert_eq!(terms("Written in Go"), ["Go"]);
+        assert_eq!(terms("你好世界"), ["世界"]);
+    }
+
+    #[test]
+    fn test_check_output() {
+        let input = "Hello. Send the invoice from Acme Cloud.";
+        let entries = glossary().matches(input);
+
+        let missing = check_output(
+            &entries,
+            input,
+            "Hallo. Senden Sie die Rechnung aus der Acme-Wolke.",
+        );
+        assert_eq!(
+            missing,
+            [MissingTerm {
+                entry: entries[0].clone(),
+                sentence: Some("Send the invoice from Acme Cloud.".to_owned()),
+            }]
+        );
+
+        assert!(check_output(&entries, input, "Die rechnung von Acme Cloud.").is_empty());
+
+        assert_eq!(
+            glossary_instructions(&entries),
+            "Translate the following terms exactly as given, keeping the terms marked as such unchanged:\n- Acme Cloud (keep unchanged)\n- invoice → Rechnung"
+        );
+        assert_eq!(glossary_instructions(&[]), "");
+    }
+
+    #[test]
+    fn test_csv() {
+        let glossary = glossary();
+        let csv = glossary.to_csv().unwrap();
+
+        assert!(csv.starts_with(
+            "source,target,case_sensitive,do_not_translate\nAcme Cloud,,false,true\n"
+        ));
+        assert_eq!(Glossary::from_csv(&csv).unwrap(), glossary);
+
+        let glossary = Glossary::from_csv("Target,Source\n\"Rechnung, die\",invoice\n").unwrap();
+        assert_eq!(
+            glossary.entries,
+            [GlossaryEntry::new("invoice", "Rechnung, die")]
+        );
+
+        assert!(Glossary::from_csv("term,translation\ninvoice,Rechnung\n").is_err());
+    }
+
+    #[test]
+    fn test_tbx() {
+        let glossary = glossary();
+        let tbx = glossary.to_tbx("en", "de");
+
+        let mut expected = glossary.clone();
+        // Terms that are not translated are written with themselves as the translation.
+        expected.entries[0].target = "Acme Cloud".to_owned();
+        assert_eq!(Glossary::from_tbx(&tbx, "en", "de").unwrap(), expected);
+
+        let tbx = r#"<?xml version="1.0"?>
+            <martif type="TBX" xml:lang="en-US">
+              <text><body>
+                <termEntry id="1">
+                  <langSet xml:lang="en-US"><tig><term>invoice</term></tig></langSet>
+                  <langSet xml:lang="de-DE"><tig><term>Rechnung</term></tig></langSet>
+                  <langSet xml:lang="fr"><tig><term>facture</term></tig></langSet>
+                </termEntry>
+              </body></text>
+            </martif>"#;
+
+        assert_eq!(
+            Glossary::from_tbx(tbx, "en", "de").unwrap().entries,
+            [GlossaryEntry::new("invoice", "Rechnung")]
+        );
+        assert!(Glossary::from_tbx("<tbx>", "en", "de").is_err());
+    }
+
+    #[test]
+    fn test_glossaries() {
+        let mut glossaries = Glossaries::default();
+        glossaries.set("en", "de", glossary());
+
+        let text = toml::to_string(&glossaries).unwrap();
+        assert_eq!(toml::from_str::<Glossaries>(&text).unwrap(), glossaries);
+        assert_eq!(glossaries.get("en", "de"), glossary());
+        assert!(glossaries.get("de", "en").is_empty());
+
+        glossaries.set("en", "de", Glossary::default());
+        assert!(glossaries.pairs.is_empty());
+    }
+}
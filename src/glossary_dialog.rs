@@ -0,0 +1,359 @@
+use crate::assets::Icons;
+use crate::config::Config;
+use crate::error::show_io_error;
+use crate::glossary::{Glossary, GlossaryEntry};
+use crate::language::Language;
+use gpui::{div, prelude::*, px, App, Entity, PathPromptOptions, Window};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::checkbox::Checkbox;
+use gpui_component::input::{Input, InputState};
+use gpui_component::label::Label;
+use gpui_component::{ActiveTheme, Sizable, WindowExt};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Returns `true` for files read and written as TBX, CSV otherwise.
+fn is_tbx(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("tbx") || extension.eq_ignore_ascii_case("xml")
+        })
+}
+
+fn read_glossary(path: &Path, source: &str, target: &str) -> io::Result<Glossary> {
+    let text = fs::read_to_string(path)?;
+
+    if is_tbx(path) {
+        Glossary::from_tbx(&text, source, target)
+    } else {
+        Glossary::from_csv(&text)
+    }
+}
+
+fn write_glossary(path: &Path, glossary: &Glossary, source: &str, target: &str) -> io::Result<()> {
+    let text = if is_tbx(path) {
+        glossary.to_tbx(source, target)
+    } else {
+        glossary.to_csv()?
+    };
+
+    fs::write(path, text)
+}
+
+struct GlossaryRow {
+    id: usize,
+    source: Entity<InputState>,
+    target: Entity<InputState>,
+    case_sensitive: bool,
+    do_not_translate: bool,
+}
+
+pub struct GlossaryForm {
+    source: Language,
+    target: Language,
+    /// Where the import and export dialogs start.
+    directory: PathBuf,
+    rows: Vec<GlossaryRow>,
+    next_id: usize,
+}
+
+impl GlossaryForm {
+    pub fn new(
+        source: Language,
+        target: Language,
+        directory: PathBuf,
+        glossary: Glossary,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut form = GlossaryForm {
+            source,
+            target,
+            directory,
+            rows: Vec::new(),
+            next_id: 0,
+        };
+
+        for entry in glossary.entries {
+            form.add(entry, window, cx);
+        }
+
+        form
+    }
+
+    fn add(&mut self, entry: GlossaryEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let source = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("glossary.source"))
+                .default_value(entry.source)
+        });
+        let target = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("glossary.target"))
+                .default_value(entry.target)
+        });
+
+        self.rows.push(GlossaryRow {
+            id: self.next_id,
+            source,
+            target,
+            case_sensitive: entry.case_sensitive,
+            do_not_translate: entry.do_not_translate,
+        });
+        self.next_id += 1;
+
+        cx.notify();
+    }
+
+    fn remove(&mut self, id: usize, cx: &mut Context<Self>) {
+        self.rows.retain(|row| row.id != id);
+        cx.notify();
+    }
+
+    fn row_mut(&mut self, id: usize) -> Option<&mut GlossaryRow> {
+        self.rows.iter_mut().find(|row| row.id == id)
+    }
+
+    /// Returns the glossary to save, skipping rows without a term.
+    pub fn glossary(&self, cx: &App) -> Glossary {
+        let entries = self
+            .rows
+            .iter()
+            .map(|row| GlossaryEntry {
+                source: row.source.read(cx).value().trim().to_owned(),
+                target: row.target.read(cx).value().trim().to_owned(),
+                case_sensitive: row.case_sensitive,
+                do_not_translate: row.do_not_translate,
+            })
+            .filter(|entry| !entry.source.is_empty())
+            .collect();
+
+        Glossary { entries }
+    }
+
+    /// Replaces the rows with the glossary merged with the imported one.
+    fn merge(&mut self, imported: Glossary, window: &mut Window, cx: &mut Context<Self>) {
+        let mut glossary = self.glossary(cx);
+        glossary.merge(imported);
+
+        self.rows.clear();
+
+        for entry in glossary.entries {
+            self.add(entry, window, cx);
+        }
+    }
+
+    fn import(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let path = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+
+        let (source, target) = (self.source.code, self.target.code);
+
+        cx.spawn_in(window, async move |this, window| {
+            let path = path.await.ok()?.ok()??.into_iter().next()?;
+
+            this.update_in(window, |this, window, cx| {
+                match read_glossary(&path, source, target) {
+                    Ok(glossary) => {
+                        let count = glossary.entries.len();
+                        this.merge(glossary, window, cx);
+
+                        window.push_notification(
+                            t!("glossary.imported", count = count).into_owned(),
+                            cx,
+                        );
+                    }
+                    Err(err) => {
+                        show_io_error(t!("error.read_file"), &path, err, window, cx);
+                    }
+                }
+            })
+            .ok()
+        })
+        .detach();
+    }
+
+    fn export(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let file_name = format!("glossary-{}-{}.csv", self.source.code, self.target.code);
+        let path = cx.prompt_for_new_path(&self.directory, Some(&file_name));
+
+        let glossary = self.glossary(cx);
+        let (source, target) = (self.source.code, self.target.code);
+
+        cx.spawn_in(window, async move |this, window| {
+            let path = path.await.ok()?.ok()??;
+            let result = write_glossary(&path, &glossary, source, target);
+
+            this.update_in(window, move |_, window, cx| match result {
+                Ok(_) => {
+                    window.push_notification(t!("saved-success").into_owned(), cx);
+                }
+                Err(err) => {
+                    show_io_error(t!("error.write_file"), &path, err, window, cx);
+                }
+            })
+            .ok()
+        })
+        .detach();
+    }
+
+    fn render_row(&self, row: &GlossaryRow, cx: &Context<Self>) -> impl IntoElement {
+        let id = row.id;
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap_2()
+            .child(div().flex_1().child(Input::new(&row.source)))
+            .child(div().flex_1().child(Input::new(&row.target)))
+            .child(
+                Checkbox::new(("case-sensitive", id))
+                    .label(t!("glossary.case-sensitive"))
+                    .checked(row.case_sensitive)
+                    .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                        if let Some(row) = this.row_mut(id) {
+                            row.case_sensitive = *checked;
+                        }
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Checkbox::new(("do-not-translate", id))
+                    .label(t!("glossary.do-not-translate"))
+                    .checked(row.do_not_translate)
+                    .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                        if let Some(row) = this.row_mut(id) {
+                            row.do_not_translate = *checked;
+                        }
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new(("delete", id))
+                    .small()
+                    .ghost()
+                    .icon(Icons::Trash2)
+                    .tooltip(t!("glossary.delete"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.remove(id, cx);
+                    })),
+            )
+    }
+}
+
+impl Render for GlossaryForm {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| self.render_row(row, cx).into_any_element())
+            .collect::<Vec<_>>();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                Label::new(format!("{} → {}", self.source.name, self.target.name))
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                Label::new(t!("glossary.description"))
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                div()
+                    .id("glossary-entries")
+                    .max_h(px(320.))
+                    .overflow_y_scroll()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .when(rows.is_empty(), |this| {
+                        this.child(
+                            Label::new(t!("glossary.empty"))
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground),
+                        )
+                    })
+                    .children(rows),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .child(
+                        Button::new("add")
+                            .small()
+                            .outline()
+                            .label(t!("glossary.add"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.add(GlossaryEntry::default(), window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("import")
+                            .small()
+                            .outline()
+                            .label(t!("glossary.import"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.import(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("export")
+                            .small()
+                            .outline()
+                            .label(t!("glossary.export"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.export(window, cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+pub fn open_glossary_dialog(
+    config: Entity<Config>,
+    source: Language,
+    target: Language,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let glossary = config.read(cx).glossary(source.code, target.code);
+    let directory = config
+        .read(cx)
+        .last_directory()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let view = cx.new(|cx| GlossaryForm::new(source, target, directory, glossary, window, cx));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        let view = view.clone();
+        let config = config.clone();
+
+        dialog
+            .confirm()
+            .title(t!("glossary").to_string())
+            .child(view.clone())
+            .on_ok(move |_, _, cx| {
+                let glossary = view.read(cx).glossary(cx);
+
+                config.update(cx, |this, cx| {
+                    this.set_glossary(source.code, target.code, glossary, cx);
+                });
+
+                true
+            })
+    });
+}
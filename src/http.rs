@@ -0,0 +1,114 @@
+use crate::ollama::Error;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::LazyLock;
+use std::task::{Context, Poll};
+use tokio::runtime::{Handle, Runtime};
+use tokio::task::{JoinError, JoinHandle};
+use url::Url;
+
+/// The runtime all requests run on, since reqwest needs tokio and the UI runs on gpui's own
+/// executor.
+pub(crate) static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("failed to create tokio runtime")
+});
+
+/// A task on [`RUNTIME`] that is aborted when dropped, so that dropping the consumer also
+/// cancels the request and closes its connection.
+pub(crate) struct AbortOnDrop<T>(pub(crate) JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+/// The request plumbing shared by the Ollama, OpenAI-compatible and llama.cpp clients. Requests
+/// and response bodies are read on [`RUNTIME`], whatever executor awaits them.
+#[derive(Clone)]
+pub(crate) struct HttpClient {
+    base_url: Url,
+    client: reqwest::Client,
+    handle: Handle,
+}
+
+impl HttpClient {
+    pub fn new(mut base_url: Url, client: reqwest::Client) -> Self {
+        // Make sure `join` appends to the base path instead of replacing its last segment.
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+
+        HttpClient {
+            base_url,
+            client,
+            handle: RUNTIME.handle().clone(),
+        }
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    pub fn get(&self, path: &'static str) -> RequestBuilder {
+        self.client.get(self.base_url.join(path).unwrap().as_str())
+    }
+
+    pub fn post(&self, path: &'static str) -> RequestBuilder {
+        self.client.post(self.base_url.join(path).unwrap().as_str())
+    }
+
+    pub fn spawn<F>(&self, future: F) -> AbortOnDrop<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        AbortOnDrop(self.handle.spawn(future))
+    }
+
+    /// Sends the request on the runtime and turns non-success responses into errors with
+    /// `from_status`, given the status and the body.
+    pub async fn send(
+        &self,
+        request: RequestBuilder,
+        from_status: impl FnOnce(StatusCode, &str) -> Error,
+    ) -> Result<Response, Error> {
+        let resp = self
+            .spawn(async move {
+                let resp = request.send().await?;
+
+                let status = resp.status();
+                if !status.is_success() {
+                    let text = resp.text().await.unwrap_or_default();
+                    return Ok(Err((status, text)));
+                }
+
+                Ok::<_, reqwest::Error>(Ok(resp))
+            })
+            .await??;
+
+        resp.map_err(|(status, text)| from_status(status, &text))
+    }
+
+    /// Reads a JSON response body on the runtime. With a read timeout, reqwest reads the body
+    /// with a tokio timer, which panics on any other executor.
+    pub async fn json<T>(&self, resp: Response) -> Result<T, Error>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        Ok(self.spawn(async move { resp.json::<T>().await }).await??)
+    }
+}
@@ -1,9 +1,12 @@
 use crate::editor::{Editor, InputStateEntityExt};
+use crate::language::Direction;
 use gpui::{prelude::*, App, Context, Entity, FocusHandle, Focusable, SharedString, Window};
 use gpui_component::input::InputState;
 
 pub struct InputEditor {
     pub state: Entity<InputState>,
+    /// The direction of the source language.
+    direction: Direction,
 }
 
 impl Focusable for InputEditor {
@@ -23,7 +26,10 @@ impl InputEditor {
         })
         .detach();
 
-        InputEditor { state }
+        InputEditor {
+            state,
+            direction: Direction::default(),
+        }
     }
 
     pub fn is_empty(&self, cx: &App) -> bool {
@@ -33,10 +39,17 @@ impl InputEditor {
     pub fn text(&self, cx: &App) -> SharedString {
         self.state.text(cx)
     }
+
+    pub fn set_direction(&mut self, direction: Direction, cx: &mut Context<Self>) {
+        if self.direction != direction {
+            self.direction = direction;
+            cx.notify();
+        }
+    }
 }
 
 impl Render for InputEditor {
     fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-        Editor::new("input-editor", &self.state)
+        Editor::new("input-editor", &self.state).direction(self.direction)
     }
 }
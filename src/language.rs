@@ -1,7 +1,29 @@
+/// How well TranslateGemma is known to translate a language.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tier {
+    /// One of the languages TranslateGemma is evaluated on, see `CORE_LANGUAGES`.
+    Core,
+    /// A regional or script variant of a core language.
+    Extended,
+    Untested,
+}
+
+/// The direction a script is written in.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Language {
     pub code: &'static str,
     pub name: &'static str,
+    pub tier: Tier,
+    /// The ISO 15924 code of the script, e.g. `Latn` or `Hans`.
+    pub script: &'static str,
+    pub direction: Direction,
 }
 
 impl PartialEq for Language {
@@ -11,19 +33,222 @@ impl PartialEq for Language {
 }
 
 impl Language {
-    #[allow(dead_code)]
-    pub fn new(code: &'static str, name: &'static str) -> Self {
-        Language { code, name }
+    pub const fn new(code: &'static str, name: &'static str) -> Self {
+        let script = script(code);
+
+        Language {
+            code,
+            name,
+            tier: tier(code),
+            script,
+            direction: direction(script),
+        }
+    }
+}
+
+/// The languages TranslateGemma is evaluated on, as codes in `LANGUAGES`. The 55 languages take
+/// 61 codes, as some are listed under several, e.g. `zh` and `zh-Hans`, or `no` and `nb`.
+const CORE_LANGUAGES: &[&str] = &[
+    "ar", "ar-EG", "ar-SA", "bg", "bn", "ca", "cs", "da", "de", "el", "en", "es", "es-MX", "et",
+    "fa", "fi", "fil-PH", "fr", "fr-CA", "gu", "he", "hi", "hr", "hu", "id", "is", "it", "ja",
+    "kn", "ko", "lt", "lv", "ml", "mr", "nb", "nl", "no", "pa", "pl", "pt", "pt-BR", "pt-PT", "ro",
+    "ru", "sk", "sl", "sr", "sv", "sw", "ta", "te", "th", "tr", "uk", "ur", "vi", "zh", "zh-Hans",
+    "zh-Hant", "zh-TW", "zu",
+];
+
+/// The scripts of languages not usually written in Latin, by primary subtag.
+const DEFAULT_SCRIPTS: &[(&str, &str)] = &[
+    ("ab", "Cyrl"),
+    ("am", "Ethi"),
+    ("ar", "Arab"),
+    ("as", "Beng"),
+    ("ba", "Cyrl"),
+    ("be", "Cyrl"),
+    ("bg", "Cyrl"),
+    ("bn", "Beng"),
+    ("bo", "Tibt"),
+    ("ce", "Cyrl"),
+    ("cv", "Cyrl"),
+    ("dv", "Thaa"),
+    ("dz", "Tibt"),
+    ("el", "Grek"),
+    ("fa", "Arab"),
+    ("gu", "Gujr"),
+    ("he", "Hebr"),
+    ("hi", "Deva"),
+    ("hy", "Armn"),
+    ("ii", "Yiii"),
+    ("iu", "Cans"),
+    ("ja", "Jpan"),
+    ("ka", "Geor"),
+    ("kk", "Cyrl"),
+    ("km", "Khmr"),
+    ("kn", "Knda"),
+    ("ko", "Kore"),
+    ("ks", "Arab"),
+    ("ky", "Cyrl"),
+    ("lo", "Laoo"),
+    ("mk", "Cyrl"),
+    ("ml", "Mlym"),
+    ("mn", "Cyrl"),
+    ("mr", "Deva"),
+    ("my", "Mymr"),
+    ("ne", "Deva"),
+    ("or", "Orya"),
+    ("os", "Cyrl"),
+    ("pa", "Guru"),
+    ("ps", "Arab"),
+    ("ru", "Cyrl"),
+    ("sa", "Deva"),
+    ("sd", "Arab"),
+    ("si", "Sinh"),
+    ("sr", "Cyrl"),
+    ("ta", "Taml"),
+    ("te", "Telu"),
+    ("tg", "Cyrl"),
+    ("th", "Thai"),
+    ("ti", "Ethi"),
+    ("tt", "Cyrl"),
+    ("ug", "Arab"),
+    ("uk", "Cyrl"),
+    ("ur", "Arab"),
+    ("yi", "Hebr"),
+    ("zh", "Hans"),
+];
+
+/// The scripts written from right to left, among the scripts of `LANGUAGES`.
+const RIGHT_TO_LEFT_SCRIPTS: &[&str] = &["Adlm", "Arab", "Hebr", "Nkoo", "Rohg", "Syrc", "Thaa"];
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+const fn contains(list: &[&str], s: &str) -> bool {
+    let mut i = 0;
+    while i < list.len() {
+        if str_eq(list[i], s) {
+            return true;
+        }
+        i += 1;
+    }
+
+    false
+}
+
+/// Splits off the first subtag of a code, e.g. `zh` and `Hant-HK` for `zh-Hant-HK`.
+const fn split_subtag(code: &str) -> (&str, &str) {
+    let bytes = code.as_bytes();
+
+    let mut i = 0;
+    while i < bytes.len() && bytes[i] != b'-' {
+        i += 1;
+    }
+
+    let (subtag, rest) = code.split_at(i);
+
+    if rest.is_empty() {
+        (subtag, rest)
+    } else {
+        (subtag, rest.split_at(1).1)
+    }
+}
+
+const fn primary_subtag(code: &str) -> &str {
+    split_subtag(code).0
+}
+
+/// Returns `true` if any subtag after the primary one is `subtag`, e.g. `TW` in `zh-TW`.
+const fn has_subtag(code: &str, subtag: &str) -> bool {
+    let mut rest = split_subtag(code).1;
+
+    while !rest.is_empty() {
+        let (next, remaining) = split_subtag(rest);
+        if str_eq(next, subtag) {
+            return true;
+        }
+        rest = remaining;
+    }
+
+    false
+}
+
+const fn tier(code: &str) -> Tier {
+    if contains(CORE_LANGUAGES, code) {
+        Tier::Core
+    } else if contains(CORE_LANGUAGES, primary_subtag(code)) {
+        Tier::Extended
+    } else {
+        Tier::Untested
+    }
+}
+
+/// Returns the script subtag of the code, or the usual script of the language.
+const fn script(code: &'static str) -> &'static str {
+    let mut rest = split_subtag(code).1;
+
+    while !rest.is_empty() {
+        let (subtag, remaining) = split_subtag(rest);
+        let bytes = subtag.as_bytes();
+
+        if bytes.len() == 4 && bytes[0].is_ascii_uppercase() {
+            return subtag;
+        }
+
+        rest = remaining;
+    }
+
+    let primary = primary_subtag(code);
+
+    if str_eq(primary, "zh")
+        && (has_subtag(code, "TW") || has_subtag(code, "HK") || has_subtag(code, "MO"))
+    {
+        return "Hant";
+    }
+
+    let mut i = 0;
+    while i < DEFAULT_SCRIPTS.len() {
+        if str_eq(DEFAULT_SCRIPTS[i].0, primary) {
+            return DEFAULT_SCRIPTS[i].1;
+        }
+        i += 1;
+    }
+
+    "Latn"
+}
+
+const fn direction(script: &str) -> Direction {
+    if contains(RIGHT_TO_LEFT_SCRIPTS, script) {
+        Direction::RightToLeft
+    } else {
+        Direction::LeftToRight
     }
 }
 
+/// Stands for the language detected from the input, offered first in the source language
+/// selector. Not in `LANGUAGES`.
+pub const AUTO_DETECT: Language = Language::new("auto", "Detect language");
+
 macro_rules! languages {
     ($( $code:literal | $name:literal ),* $(,)?) => {
-        [$(Language { code: $code, name: $name}),*]
+        [$(Language::new($code, $name)),*]
     };
 }
 
-pub static LANGUAGES: &'static [Language] = &languages![
+pub static LANGUAGES: &[Language] = &languages![
     "aa" | "Afar",
     "aa-DJ" | "Afar",
     "aa-ER" | "Afar",
@@ -606,3 +831,44 @@ pub static LANGUAGES: &'static [Language] = &languages![
     "zu" | "Zulu",
     "zu-ZA" | "Zulu",
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn language(code: &str) -> Language {
+        *LANGUAGES.iter().find(|lang| lang.code == code).unwrap()
+    }
+
+    #[test]
+    fn test_tier() {
+        assert_eq!(language("de").tier, Tier::Core);
+        assert_eq!(language("pt-BR").tier, Tier::Core);
+        assert_eq!(language("de-AT").tier, Tier::Extended);
+        assert_eq!(language("zh-Hans-SG").tier, Tier::Extended);
+        assert_eq!(language("aa-DJ").tier, Tier::Untested);
+
+        assert_eq!(
+            LANGUAGES
+                .iter()
+                .filter(|lang| lang.tier == Tier::Core)
+                .count(),
+            CORE_LANGUAGES.len()
+        );
+    }
+
+    #[test]
+    fn test_script() {
+        assert_eq!(language("en").script, "Latn");
+        assert_eq!(language("ru").script, "Cyrl");
+        assert_eq!(language("sr-Latn-BA").script, "Latn");
+        assert_eq!(language("zh").script, "Hans");
+        assert_eq!(language("zh-TW").script, "Hant");
+        assert_eq!(language("zh-Hant-MY").script, "Hant");
+
+        assert_eq!(language("ar-EG").direction, Direction::RightToLeft);
+        assert_eq!(language("ff-Adlm-GH").direction, Direction::RightToLeft);
+        assert_eq!(language("ff-Latn").direction, Direction::LeftToRight);
+        assert_eq!(language("he").direction, Direction::RightToLeft);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::language::{Language, LANGUAGES};
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Gurmukhi,
+    Gujarati,
+    Tamil,
+    Telugu,
+    Kannada,
+    Malayalam,
+    Sinhala,
+    Thai,
+    Lao,
+    Tibetan,
+    Myanmar,
+    Georgian,
+    Hangul,
+    Ethiopic,
+    Khmer,
+    Kana,
+    Han,
+}
+
+impl Script {
+    fn of(c: char) -> Option<Script> {
+        let script = match c as u32 {
+            0x41..=0x5A | 0x61..=0x7A | 0xC0..=0xD6 | 0xD8..=0xF6 | 0xF8..=0x24F => Script::Latin,
+            0x1E00..=0x1EFF => Script::Latin,
+            0x370..=0x3FF | 0x1F00..=0x1FFF => Script::Greek,
+            0x400..=0x52F => Script::Cyrillic,
+            0x530..=0x58F => Script::Armenian,
+            0x590..=0x5FF => Script::Hebrew,
+            0x600..=0x6FF | 0x750..=0x77F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Script::Arabic,
+            0x900..=0x97F => Script::Devanagari,
+            0x980..=0x9FF => Script::Bengali,
+            0xA00..=0xA7F => Script::Gurmukhi,
+            0xA80..=0xAFF => Script::Gujarati,
+            0xB80..=0xBFF => Script::Tamil,
+            0xC00..=0xC7F => Script::Telugu,
+            0xC80..=0xCFF => Script::Kannada,
+            0xD00..=0xD7F => Script::Malayalam,
+            0xD80..=0xDFF => Script::Sinhala,
+            0xE00..=0xE7F => Script::Thai,
+            0xE80..=0xEFF => Script::Lao,
+            0xF00..=0xFFF => Script::Tibetan,
+            0x1000..=0x109F => Script::Myanmar,
+            0x10A0..=0x10FF => Script::Georgian,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+            0x1200..=0x139F => Script::Ethiopic,
+            0x1780..=0x17FF => Script::Khmer,
+            0x3040..=0x30FF | 0x31F0..=0x31FF => Script::Kana,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FFFF => Script::Han,
+            _ => return None,
+        };
+
+        Some(script)
+    }
+
+    /// How much a character counts towards the script of the text. A Chinese or Japanese
+    /// character carries about as much as a few letters of a word, so English names in Chinese
+    /// text don't outweigh it.
+    fn weight(self) -> f32 {
+        match self {
+            Script::Han | Script::Kana | Script::Hangul => 3.0,
+            _ => 1.0,
+        }
+    }
+
+    /// The language written in the script, for scripts used by a single language.
+    fn language(self) -> Option<&'static str> {
+        let code = match self {
+            Script::Greek => "el",
+            Script::Armenian => "hy",
+            Script::Hebrew => "he",
+            Script::Bengali => "bn",
+            Script::Gurmukhi => "pa",
+            Script::Gujarati => "gu",
+            Script::Tamil => "ta",
+            Script::Telugu => "te",
+            Script::Kannada => "kn",
+            Script::Malayalam => "ml",
+            Script::Sinhala => "si",
+            Script::Thai => "th",
+            Script::Lao => "lo",
+            Script::Tibetan => "bo",
+            Script::Myanmar => "my",
+            Script::Georgian => "ka",
+            Script::Hangul => "ko",
+            Script::Ethiopic => "am",
+            Script::Khmer => "km",
+            Script::Kana => "ja",
+            _ => return None,
+        };
+
+        Some(code)
+    }
+
+    /// The languages told apart by their common words and letters, the first being the guess
+    /// when nothing matches.
+    fn profiles(self) -> &'static [Profile] {
+        match self {
+            Script::Latin => LATIN,
+            Script::Cyrillic => CYRILLIC,
+            Script::Arabic => ARABIC,
+            Script::Devanagari => DEVANAGARI,
+            _ => &[],
+        }
+    }
+}
+
+/// The common words and distinctive letters of a language.
+struct Profile {
+    code: &'static str,
+    words: &'static [&'static str],
+    letters: &'static str,
+}
+
+macro_rules! profiles {
+    ($( $code:literal: [$($word:literal)*] $letters:literal ),* $(,)?) => {
+        &[$(Profile { code: $code, words: &[$($word),*], letters: $letters }),*]
+    };
+}
+
+static LATIN: &[Profile] = profiles![
+    "en": ["the" "and" "of" "to" "in" "is" "that" "it" "for" "you" "was" "with" "on" "are" "this" "be" "have" "not" "at" "by" "from" "or" "but" "they" "what" "can"] "",
+    "fr": ["le" "la" "les" "de" "des" "et" "est" "un" "une" "que" "qui" "pas" "pour" "dans" "sur" "avec" "ce" "il" "elle" "vous" "nous" "je" "du" "au" "sont"] "éèêàçùœ",
+    "de": ["der" "die" "das" "und" "ist" "nicht" "ein" "eine" "zu" "den" "von" "mit" "sich" "des" "auf" "für" "im" "dem" "ich" "sie" "es" "auch" "wir" "wie"] "ßäöü",
+    "es": ["el" "la" "los" "las" "de" "que" "y" "en" "un" "una" "es" "por" "con" "para" "no" "se" "del" "al" "lo" "como" "más" "pero" "su" "está"] "ñ¿¡áíóú",
+    "it": ["il" "lo" "la" "gli" "le" "di" "che" "e" "è" "un" "una" "per" "non" "con" "del" "della" "sono" "si" "da" "al" "ma" "come" "anche" "questo"] "àèìòù",
+    "pt": ["o" "a" "os" "as" "de" "que" "e" "é" "um" "uma" "para" "com" "não" "em" "do" "da" "dos" "das" "se" "no" "na" "por" "mais" "você"] "ãõçáêô",
+    "nl": ["de" "het" "een" "en" "van" "is" "dat" "niet" "op" "te" "zijn" "met" "voor" "je" "ik" "die" "er" "aan" "ook" "maar" "wat"] "",
+    "sv": ["och" "att" "det" "är" "en" "som" "på" "för" "med" "av" "inte" "jag" "har" "till" "den" "om" "ett" "vi" "kan"] "åäö",
+    "da": ["og" "at" "det" "er" "en" "som" "på" "for" "med" "af" "ikke" "jeg" "har" "til" "den" "om" "et" "vi" "kan"] "æøå",
+    "nb": ["og" "at" "det" "er" "en" "som" "på" "for" "med" "av" "ikke" "jeg" "har" "til" "den" "om" "et" "vi" "kan"] "æøå",
+    "fi": ["ja" "on" "ei" "se" "että" "hän" "oli" "ovat" "mutta" "kun" "myös" "tai" "joka" "tämä" "minä" "sinä" "me" "te"] "äö",
+    "pl": ["i" "w" "na" "nie" "się" "z" "do" "że" "to" "jest" "jak" "co" "ale" "o" "po" "tak" "są" "dla" "od" "przez"] "ąęłńśźż",
+    "cs": ["a" "je" "se" "na" "že" "v" "to" "s" "z" "do" "jsou" "ale" "jak" "by" "pro" "tak" "co" "od" "jsem" "není"] "řěůčšž",
+    "sk": ["a" "je" "sa" "na" "že" "v" "to" "s" "z" "do" "sú" "ale" "ako" "by" "pre" "tak" "čo" "od" "som" "nie"] "ľĺŕôä",
+    "hu": ["a" "az" "és" "hogy" "nem" "is" "egy" "van" "de" "meg" "ez" "el" "csak" "már" "mint" "volt"] "őű",
+    "ro": ["și" "în" "de" "la" "cu" "nu" "să" "este" "o" "un" "pe" "care" "din" "pentru" "mai" "ce" "sunt"] "ășțâî",
+    "tr": ["ve" "bir" "bu" "da" "de" "için" "ile" "çok" "ne" "değil" "ama" "gibi" "olan" "daha" "var" "ben" "sen"] "şğı",
+    "id": ["yang" "dan" "di" "ini" "itu" "dengan" "untuk" "tidak" "dari" "dalam" "akan" "ada" "saya" "kami" "pada"] "",
+    "vi": ["và" "của" "là" "có" "không" "những" "một" "được" "cho" "người" "này" "với" "các" "trong" "đã"] "ơưđạảấầậắặẹẻẽếềểễệịọỏốồổộớờởợụủứừửữự",
+    "ca": ["el" "la" "els" "les" "de" "i" "que" "és" "un" "una" "per" "amb" "no" "en" "al" "del" "són" "com" "però"] "ŀ",
+    "hr": ["i" "u" "je" "se" "na" "da" "za" "su" "ne" "od" "to" "kao" "što" "ali" "sa" "iz"] "čćđšž",
+];
+
+static CYRILLIC: &[Profile] = profiles![
+    "ru": ["и" "в" "не" "на" "что" "я" "с" "он" "как" "это" "по" "но" "из" "у" "за" "от" "мы" "вы"] "ыэъё",
+    "uk": ["і" "в" "не" "на" "що" "я" "з" "він" "як" "це" "та" "до" "від" "ми" "ви"] "іїєґ",
+    "bg": ["и" "в" "не" "на" "че" "аз" "с" "той" "как" "това" "да" "се" "от" "за" "ние"] "ъ",
+    "sr": ["и" "у" "не" "на" "да" "је" "се" "за" "од" "су" "то" "као" "што"] "ђјљњћџ",
+    "be": ["і" "ў" "не" "на" "што" "я" "з" "ён" "як" "гэта" "ад" "мы"] "ўі",
+    "kk": ["және" "бұл" "мен" "да" "не" "бір" "деп" "үшін"] "әғқңөұүһ",
+];
+
+static ARABIC: &[Profile] = profiles![
+    "ar": ["في" "من" "على" "أن" "إلى" "هذا" "التي" "الذي" "عن" "مع" "كان" "ما" "لا"] "ةى",
+    "fa": ["و" "در" "به" "از" "که" "این" "را" "با" "است" "برای" "آن" "می"] "پچژگ",
+    "ur": ["اور" "کے" "میں" "ہے" "کی" "سے" "کو" "کا" "نہیں" "یہ"] "ٹڈڑںےھ",
+];
+
+static DEVANAGARI: &[Profile] = profiles![
+    "hi": ["है" "के" "में" "की" "और" "से" "को" "का" "नहीं" "यह" "हैं" "था"] "",
+    "mr": ["आहे" "आणि" "च्या" "ला" "हे" "नाही" "या" "की" "होते"] "ळ",
+    "ne": ["छ" "र" "को" "मा" "हो" "यो" "गर्न" "छन्" "पनि" "भएको"] "",
+];
+
+/// Simplified characters whose traditional forms differ, and the traditional forms.
+const SIMPLIFIED: &str =
+    "这说们来对时会个为发过还没开关门间问见长东车书买卖学国后语读认让给钱电话听写应该实现";
+const TRADITIONAL: &str =
+    "這說們來對時會個為發過還沒開關門間問見長東車書買賣學國後語讀認讓給錢電話聽寫應該實現";
+
+/// Japanese forms of kanji that differ from both their simplified and traditional Chinese forms,
+/// which tell Japanese apart from Chinese in text written without kana.
+const SHINJITAI: &str = "験駅気図広売読続歳沢辺変実楽薬転伝対単悪県権様桜関鉄営検険乗効拡択黒恵戦涙巻徳発経絵帰総聴価値払仏渋訳圧労";
+
+/// A guess at the language of a text.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Detection {
+    pub language: Language,
+    /// From 0 to 1.
+    pub confidence: f32,
+}
+
+/// How sure a detection is, coarse enough not to change with every keystroke.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Certainty {
+    Low,
+    Medium,
+    High,
+}
+
+impl Detection {
+    pub fn certainty(&self) -> Certainty {
+        if self.confidence >= 0.75 {
+            Certainty::High
+        } else if self.confidence >= 0.4 {
+            Certainty::Medium
+        } else {
+            Certainty::Low
+        }
+    }
+}
+
+fn language(code: &str) -> Option<Language> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.code == code)
+        .copied()
+}
+
+/// Picks the language of the profile with the most common words and distinctive letters in the
+/// text, with how far ahead of the next one it is.
+fn score_profiles(text: &str, profiles: &[Profile]) -> Option<(&'static str, f32)> {
+    let lowercase = text.to_lowercase();
+    let words = lowercase
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut scores = profiles
+        .iter()
+        .map(|profile| {
+            let words = words
+                .iter()
+                .filter(|word| profile.words.contains(word))
+                .count();
+            let letters = lowercase
+                .chars()
+                .filter(|c| profile.letters.contains(*c))
+                .count();
+
+            (profile.code, words as f32 + letters as f32)
+        })
+        .collect::<Vec<_>>();
+
+    // Keeps the table order for ties, so the first profile is the fallback.
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let (code, best) = *scores.first()?;
+    let second = scores.get(1).map_or(0.0, |score| score.1);
+
+    if best == 0.0 {
+        return Some((code, 0.1));
+    }
+
+    // A single matching word is weak evidence, however far ahead it is.
+    let evidence = (best / 4.0).min(1.0);
+
+    Some((code, (best - second) / best * evidence))
+}
+
+/// Guesses the language of the text from its script, then its common words and distinctive
+/// letters. Returns `None` for text without letters.
+pub fn detect_language(text: &str) -> Option<Detection> {
+    let mut scripts = HashMap::<Script, f32>::new();
+
+    for script in text.chars().filter_map(Script::of) {
+        *scripts.entry(script).or_default() += script.weight();
+    }
+
+    // Japanese mixes kanji with kana, so any amount of kana counts the kanji as Japanese.
+    if let Some(kana) = scripts.get(&Script::Kana).copied()
+        && let Some(han) = scripts.remove(&Script::Han)
+    {
+        scripts.insert(Script::Kana, kana + han);
+    }
+
+    let total = scripts.values().sum::<f32>();
+    let (script, weight) = scripts.into_iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let share = weight / total;
+
+    let (code, confidence) = if let Some(code) = script.language() {
+        (code, 1.0)
+    } else if script == Script::Han && text.chars().any(|c| SHINJITAI.contains(c)) {
+        ("ja", 1.0)
+    } else if script == Script::Han {
+        let simplified = text.chars().filter(|c| SIMPLIFIED.contains(*c)).count();
+        let traditional = text.chars().filter(|c| TRADITIONAL.contains(*c)).count();
+
+        if traditional > simplified {
+            ("zh-Hant", 1.0)
+        } else {
+            ("zh-Hans", 1.0)
+        }
+    } else {
+        score_profiles(text, script.profiles())?
+    };
+
+    Some(Detection {
+        language: language(code)?,
+        confidence: share * confidence,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> &'static str {
+        detect_language(text).unwrap().language.code
+    }
+
+    #[test]
+    fn test_detect_script() {
+        assert_eq!(detect("你好，世界！这是一个测试。"), "zh-Hans");
+        assert_eq!(detect("這是一個測試，我們來看看。"), "zh-Hant");
+        assert_eq!(detect("これは日本語の文章です。"), "ja");
+        assert_eq!(detect("東京駅発新幹線"), "ja");
+        assert_eq!(detect("試験結果"), "ja");
+        assert_eq!(detect("안녕하세요, 세계!"), "ko");
+        assert_eq!(detect("Καλημέρα κόσμε"), "el");
+        assert_eq!(detect("使用 GitHub 部署应用程序"), "zh-Hans");
+        assert_eq!(detect("Привет, как это работает?"), "ru");
+        assert_eq!(detect("Привіт, як це працює? Це їхній дім."), "uk");
+
+        assert!(detect_language("123 !?").is_none());
+        assert_eq!(detect_language("สวัสดีครับ").unwrap().confidence, 1.0);
+    }
+
+    #[test]
+    fn test_detect_words() {
+        assert_eq!(detect("The quick brown fox jumps over the lazy dog"), "en");
+        assert_eq!(detect("Je ne sais pas ce que vous voulez dire."), "fr");
+        assert_eq!(
+            detect("Ich weiß nicht, was du meinst, aber es ist gut."),
+            "de"
+        );
+        assert_eq!(detect("¿Dónde está la biblioteca? No lo sé."), "es");
+        assert_eq!(detect("Não sei o que você quer dizer com isso."), "pt");
+        assert_eq!(detect("Tôi không biết bạn đang nói gì."), "vi");
+
+        let weak = detect_language("Hello").unwrap();
+        assert_eq!(weak.language.code, "en");
+        assert!(weak.confidence < 0.5);
+        assert_eq!(weak.certainty(), Certainty::Low);
+
+        let strong = detect_language("This is the house that they built for you.").unwrap();
+        assert!(strong.confidence > 0.5);
+        assert_ne!(strong.certainty(), Certainty::Low);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::translation_style::TranslationStyle;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// Returns the config key of a language pair, e.g. `en:zh-Hans`.
+pub fn pair_key(source: &str, target: &str) -> String {
+    format!("{source}:{target}")
+}
+
+/// How to translate from one language to another, e.g. `[language_pairs."en:zh-Hans"]` in the
+/// config file.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PairSettings {
+    /// The name of the prompt template, the built-in one when unset.
+    pub template: Option<String>,
+    /// A description of the texts, e.g. `UI strings of a photo editor`.
+    pub context: Option<String>,
+    #[serde(skip_serializing_if = "TranslationStyle::is_empty")]
+    pub style: TranslationStyle,
+}
+
+impl PairSettings {
+    pub fn is_empty(&self) -> bool {
+        self == &PairSettings::default()
+    }
+}
+
+/// How many recently used language pairs are kept.
+pub const MAX_RECENT_PAIRS: usize = 5;
+
+/// A source and a target language, by code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguagePair {
+    pub source: String,
+    pub target: String,
+}
+
+impl LanguagePair {
+    pub fn new(source: impl Into<String>, target: impl Into<String>) -> Self {
+        LanguagePair {
+            source: source.into(),
+            target: target.into(),
+        }
+    }
+}
+
+/// The language pairs offered for switching in one click, e.g. `[[quick_pairs.favorites]]` in
+/// the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuickPairs {
+    /// Pinned by the user, in the order they were added.
+    pub favorites: Vec<LanguagePair>,
+    /// The newest first. A pair used again keeps its place, so its shortcut doesn't change.
+    pub recent: Vec<LanguagePair>,
+}
+
+impl QuickPairs {
+    /// Adds a new pair to the front of the recent pairs, dropping the oldest. Returns `true` if
+    /// they changed.
+    pub fn record(&mut self, pair: LanguagePair) -> bool {
+        if self.recent.contains(&pair) {
+            return false;
+        }
+
+        self.recent.insert(0, pair);
+        self.recent.truncate(MAX_RECENT_PAIRS);
+
+        true
+    }
+
+    pub fn is_favorite(&self, pair: &LanguagePair) -> bool {
+        self.favorites.contains(pair)
+    }
+
+    /// Pins the pair, or unpins it if it is a favorite already.
+    pub fn toggle_favorite(&mut self, pair: LanguagePair) {
+        if self.is_favorite(&pair) {
+            self.favorites.retain(|favorite| favorite != &pair);
+        } else {
+            self.favorites.push(pair);
+        }
+    }
+
+    /// Returns the recent pairs that are not favorites, listed after them.
+    pub fn other_recent(&self) -> impl Iterator<Item = &LanguagePair> {
+        self.recent.iter().filter(|pair| !self.is_favorite(pair))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quick_pairs() {
+        let mut pairs = QuickPairs::default();
+
+        assert!(pairs.record(LanguagePair::new("en", "de")));
+        assert!(!pairs.record(LanguagePair::new("en", "de")));
+
+        for target in ["fr", "es", "it", "ja", "de", "ko"] {
+            pairs.record(LanguagePair::new("en", target));
+        }
+        assert_eq!(
+            pairs
+                .recent
+                .iter()
+                .map(|pair| &pair.target)
+                .collect::<Vec<_>>(),
+            ["ko", "ja", "it", "es", "fr"]
+        );
+
+        pairs.toggle_favorite(LanguagePair::new("de", "en"));
+        pairs.toggle_favorite(LanguagePair::new("en", "ja"));
+        assert_eq!(pairs.other_recent().count(), 4);
+        assert!(!pairs.other_recent().any(|pair| pair.target == "ja"));
+
+        // Using pairs again reorders neither the recent pairs nor the favorites, which the
+        // shortcuts refer to by place.
+        assert!(!pairs.record(LanguagePair::new("en", "ja")));
+        assert!(!pairs.record(LanguagePair::new("en", "es")));
+        assert_eq!(pairs.recent[3], LanguagePair::new("en", "es"));
+        assert_eq!(
+            pairs.favorites,
+            [LanguagePair::new("de", "en"), LanguagePair::new("en", "ja")]
+        );
+
+        pairs.toggle_favorite(LanguagePair::new("de", "en"));
+        assert!(!pairs.is_favorite(&LanguagePair::new("de", "en")));
+        assert_eq!(pairs.favorites, [LanguagePair::new("en", "ja")]);
+    }
+}
@@ -1,9 +1,11 @@
-use crate::language::{Language, LANGUAGES};
-use gpui::{prelude::*, App, AppContext, Entity, EventEmitter, SharedString, Window};
-use gpui_component::select::{SearchableVec, Select, SelectEvent, SelectItem, SelectState};
-use gpui_component::IconName;
+use crate::language::{Language, Tier, AUTO_DETECT, LANGUAGES};
+use crate::language_detection::{Certainty, Detection};
+use gpui::{prelude::*, App, AppContext, Entity, EventEmitter, SharedString, Task, Window};
+use gpui_component::select::{Select, SelectDelegate, SelectEvent, SelectItem, SelectState};
+use gpui_component::{IconName, IndexPath};
 use icu_experimental::displaynames::LocaleDisplayNamesFormatter;
 use icu_locale::{locale, Locale};
+use std::borrow::Cow;
 
 #[derive(Debug, Clone)]
 pub struct LanguageItem {
@@ -27,7 +29,7 @@ impl LanguageItem {
         let display_name = LocaleDisplayNamesFormatter::try_new(locale.into(), Default::default())
             .expect("failed to load compiled data");
 
-        LANGUAGES
+        let mut items = LANGUAGES
             .iter()
             .cloned()
             .map(|lang| {
@@ -35,15 +37,35 @@ impl LanguageItem {
 
                 LanguageItem::new(lang, display_name.of(&locale).into_owned())
             })
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>();
+
+        // Core languages first, so they also come first among search results.
+        items.sort_by_key(|item| item.language.tier);
+        items
+    }
+}
+
+fn tier_tag(tier: Tier) -> Option<Cow<'static, str>> {
+    match tier {
+        Tier::Core => None,
+        Tier::Extended => Some(t!("language.extended")),
+        Tier::Untested => Some(t!("language.untested")),
     }
 }
 
 impl SelectItem for LanguageItem {
     type Value = Language;
 
+    /// Tags the languages TranslateGemma is not evaluated on, e.g. `Afar (Djibouti) · Untested`.
     fn title(&self) -> SharedString {
-        self.display_name.clone()
+        if self.language == AUTO_DETECT {
+            return self.display_name.clone();
+        }
+
+        match tier_tag(self.language.tier) {
+            Some(tag) => format!("{} · {tag}", self.display_name).into(),
+            None => self.display_name.clone(),
+        }
     }
 
     fn value(&self) -> &Self::Value {
@@ -51,38 +73,148 @@ impl SelectItem for LanguageItem {
     }
 
     fn matches(&self, query: &str) -> bool {
+        self.rank(query).is_some()
+    }
+}
+
+/// How well a language matches a search, best first.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    /// The name or code starts with the query.
+    Prefix,
+    /// The name or code contains the query.
+    Contains,
+    /// The query is the script code, e.g. `Arab`, which many languages share.
+    Script,
+}
+
+impl LanguageItem {
+    fn rank(&self, query: &str) -> Option<Rank> {
         let query = query.to_lowercase();
+        let fields = [
+            self.display_name.to_lowercase(),
+            self.language.name.to_lowercase(),
+            self.language.code.to_lowercase(),
+        ];
+
+        if fields.iter().any(|field| field.starts_with(&query)) {
+            Some(Rank::Prefix)
+        } else if fields.iter().any(|field| field.contains(&query)) {
+            Some(Rank::Contains)
+        } else if self.language.script.eq_ignore_ascii_case(&query) {
+            Some(Rank::Script)
+        } else {
+            None
+        }
+    }
+}
+
+/// The languages of a selector, searched by name, code or script.
+#[derive(Debug, Clone)]
+pub struct LanguageList {
+    items: Vec<LanguageItem>,
+    matched_items: Vec<LanguageItem>,
+}
+
+impl LanguageList {
+    pub fn new(items: Vec<LanguageItem>) -> Self {
+        LanguageList {
+            matched_items: items.clone(),
+            items,
+        }
+    }
+}
+
+impl SelectDelegate for LanguageList {
+    type Item = LanguageItem;
+
+    fn items_count(&self, _: usize) -> usize {
+        self.matched_items.len()
+    }
+
+    fn item(&self, ix: IndexPath) -> Option<&Self::Item> {
+        self.matched_items.get(ix.row)
+    }
+
+    fn position<V>(&self, value: &V) -> Option<IndexPath>
+    where
+        Self::Item: SelectItem<Value = V>,
+        V: PartialEq,
+    {
+        self.matched_items
+            .iter()
+            .position(|item| item.value() == value)
+            .map(|ix| IndexPath::default().row(ix))
+    }
+
+    /// Ranks name and code matches above script matches. The sort is stable and the items are
+    /// sorted by tier, so core languages come first within each rank.
+    fn perform_search(
+        &mut self,
+        query: &str,
+        _: &mut Window,
+        _: &mut Context<SelectState<Self>>,
+    ) -> Task<()> {
+        let mut matches = self
+            .items
+            .iter()
+            .filter_map(|item| Some((item.rank(query)?, item)))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(rank, _)| *rank);
 
-        self.display_name.to_lowercase().contains(&query)
-            || self.language.name.to_lowercase().contains(&query)
-            || self.language.code.to_lowercase().contains(&query)
+        self.matched_items = matches.into_iter().map(|(_, item)| item.clone()).collect();
+        Task::ready(())
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct LanguageSelector {
-    state: Entity<SelectState<SearchableVec<LanguageItem>>>,
+    state: Entity<SelectState<LanguageList>>,
+    /// The languages with their names in the current locale.
+    items: Vec<LanguageItem>,
+    /// Offers Detect language first, for the source language.
+    detect: bool,
+    detection: Option<Detection>,
 }
 
 impl LanguageSelector {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self::build(false, window, cx)
+    }
+
+    /// Creates a selector with a Detect language entry first.
+    pub fn with_detection(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self::build(true, window, cx)
+    }
+
+    fn build(detect: bool, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let items = LanguageItem::all();
+        let detect_item = detect.then(|| LanguageItem::new(AUTO_DETECT, t!("detect-language")));
+        let all_items = detect_item
+            .into_iter()
+            .chain(items.iter().cloned())
+            .collect();
+
         LanguageSelector {
-            state: Self::setup_state(window, cx),
+            state: Self::setup_state(all_items, window, cx),
+            items,
+            detect,
+            detection: None,
         }
     }
 
     fn setup_state(
+        items: Vec<LanguageItem>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Entity<SelectState<SearchableVec<LanguageItem>>> {
+    ) -> Entity<SelectState<LanguageList>> {
         let state = cx.new(|cx| {
-            SelectState::new(SearchableVec::new(LanguageItem::all()), None, window, cx)
-                .searchable(true)
+            SelectState::new(LanguageList::new(items), None, window, cx).searchable(true)
         });
 
         cx.subscribe(
             &state,
-            |_, _, event: &SelectEvent<SearchableVec<LanguageItem>>, cx| match event {
+            |_, _, event: &SelectEvent<LanguageList>, cx| match event {
                 SelectEvent::Confirm(lang) => {
                     cx.emit(LanguageSelectEvent(lang.map(|lang| lang.clone())))
                 }
@@ -93,18 +225,111 @@ impl LanguageSelector {
         state
     }
 
+    /// Returns the Detect language entry, showing the detected language once there is one.
+    fn detect_item(&self) -> LanguageItem {
+        let detected = self.detection.and_then(|detection| {
+            self.items
+                .iter()
+                .find(|item| item.language == detection.language)
+                .map(|item| (item.display_name.clone(), detection.certainty()))
+        });
+
+        let title = match detected {
+            Some((language, certainty)) => t!(
+                "detect-language.detected",
+                language = language,
+                certainty = match certainty {
+                    Certainty::Low => t!("detect-language.low"),
+                    Certainty::Medium => t!("detect-language.medium"),
+                    Certainty::High => t!("detect-language.high"),
+                }
+            ),
+            None => t!("detect-language"),
+        };
+
+        LanguageItem::new(AUTO_DETECT, title)
+    }
+
+    fn all_items(&self) -> Vec<LanguageItem> {
+        let detect_item = self.detect.then(|| self.detect_item());
+
+        detect_item
+            .into_iter()
+            .chain(self.items.iter().cloned())
+            .collect()
+    }
+
     pub fn reset_state(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        self.state = Self::setup_state(window, cx);
+        self.state = Self::setup_state(self.all_items(), window, cx);
     }
 
-    pub fn update_items(&self, window: &mut Window, cx: &mut Context<Self>) {
+    /// Replaces the items, keeping the selection.
+    fn refresh_items(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let items = self.all_items();
+
         self.state.update(cx, |this, cx| {
-            this.set_items(SearchableVec::new(LanguageItem::all()), window, cx)
+            let selected = this.selected_value().cloned();
+
+            this.set_items(LanguageList::new(items), window, cx);
+
+            if let Some(selected) = selected {
+                this.set_selected_value(&selected, window, cx);
+            }
         })
     }
 
+    pub fn update_items(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.items = LanguageItem::all();
+        self.refresh_items(window, cx);
+    }
+
+    /// Shows the language detected from the input in the Detect language entry. The items are
+    /// only rebuilt when the language or its certainty changes, not on every keystroke.
+    pub fn set_detection(
+        &mut self,
+        detection: Option<Detection>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let key = |detection: Option<Detection>| {
+            detection.map(|detection| (detection.language, detection.certainty()))
+        };
+        let changed = key(self.detection) != key(detection);
+        self.detection = detection;
+
+        if self.detect && changed {
+            self.refresh_items(window, cx);
+        }
+    }
+
+    /// Returns `true` if Detect language is selected.
+    pub fn is_detecting(&self, cx: &App) -> bool {
+        self.state.read(cx).selected_value() == Some(&AUTO_DETECT)
+    }
+
+    /// Returns `true` if Detect language is selected without a confident guess.
+    pub fn is_guessing(&self, cx: &App) -> bool {
+        self.is_detecting(cx)
+            && self
+                .detection
+                .is_none_or(|detection| detection.certainty() == Certainty::Low)
+    }
+
+    /// Returns the selected language, or the detected one when Detect language is selected.
     pub fn selected_language(&self, cx: &App) -> Option<Language> {
-        self.state.read(cx).selected_value().cloned()
+        if self.is_detecting(cx) {
+            self.detection.map(|detection| detection.language)
+        } else {
+            self.state.read(cx).selected_value().cloned()
+        }
+    }
+
+    /// Returns the name of a language in the current locale.
+    pub fn display_name(&self, language_code: &str) -> Option<SharedString> {
+        self.items
+            .iter()
+            .find(|item| item.language.code == language_code)
+            .map(|item| item.display_name.clone())
     }
 
     pub fn set_selected_language(
@@ -115,6 +340,7 @@ impl LanguageSelector {
     ) {
         let lang = LANGUAGES
             .iter()
+            .chain(self.detect.then_some(&AUTO_DETECT))
             .find(|lang| lang.code == language_code.as_ref());
 
         if let Some(lang) = lang {
@@ -0,0 +1,198 @@
+use crate::http::HttpClient;
+use crate::llama_cpp::types::*;
+use crate::ollama::{ConnectionOptions, Error};
+use crate::openai::{error_from_status, read_events};
+use futures_util::stream::BoxStream;
+use reqwest::header::ACCEPT;
+use reqwest::{RequestBuilder, Response};
+use url::Url;
+
+/// A client for the native API of llama.cpp's `llama-server`.
+#[derive(Clone)]
+pub struct Client {
+    http: HttpClient,
+}
+
+impl Client {
+    pub fn new(base_url: Url) -> Self {
+        Client::with_client(base_url, reqwest::Client::new())
+    }
+
+    /// Creates a client with custom headers, certificates, proxy or timeouts, e.g. an
+    /// `Authorization` header for a server started with `--api-key`.
+    pub fn with_options(base_url: Url, options: &ConnectionOptions) -> Result<Self, Error> {
+        Ok(Client::with_client(base_url, options.build_client()?))
+    }
+
+    fn with_client(base_url: Url, client: reqwest::Client) -> Self {
+        Client {
+            http: HttpClient::new(base_url, client),
+        }
+    }
+
+    pub fn base_url(&self) -> &Url {
+        self.http.base_url()
+    }
+
+    /// Sends the request on the runtime and turns non-success responses into errors.
+    async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        // The server has a single model, so there is no model to report as missing.
+        self.http
+            .send(request, |status, text| {
+                error_from_status(status, text, None)
+            })
+            .await
+    }
+}
+
+impl Client {
+    /// Returns the loaded model and its settings. Fails with `503 Service Unavailable` while
+    /// the model is loading.
+    pub async fn props(&self) -> Result<Props, Error> {
+        let resp = self.send(self.http.get("props")).await?;
+
+        self.http.json::<Props>(resp).await
+    }
+
+    /// Starts a streamed completion of a raw prompt.
+    pub async fn completion(
+        &self,
+        mut request: CompletionRequest,
+    ) -> Result<BoxStream<'static, Result<CompletionChunk, Error>>, Error> {
+        request.stream = Some(true);
+
+        let resp = self
+            .send(
+                self.http
+                    .post("completion")
+                    .header(ACCEPT, "text/event-stream")
+                    .json(&request),
+            )
+            .await?;
+
+        Ok(read_events(&self.http, resp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+
+    /// Starts a server that answers one request with the given raw HTTP response, and returns
+    /// the request it received.
+    async fn respond_once(response: &'static str) -> (Url, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 8192];
+
+            let mut request = String::new();
+
+            // Requests with a body end with its closing brace.
+            while !request.contains("\r\n\r\n")
+                || (request.starts_with("POST") && !request.ends_with('}'))
+            {
+                let n = socket.read(&mut buf).await.unwrap();
+                assert!(n > 0);
+                request.push_str(&String::from_utf8_lossy(&buf[..n]));
+            }
+
+            tx.send(request).ok();
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        (url, rx)
+    }
+
+    #[tokio::test]
+    async fn test_props() {
+        let (url, _) = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"default_generation_settings\":{\"n_ctx\":8192,\"params\":{}},\
+             \"total_slots\":1,\
+             \"model_path\":\"/models/translategemma-4b-it-Q4_K_M.gguf\"}",
+        )
+        .await;
+
+        let props = Client::new(url).props().await.unwrap();
+
+        assert_eq!(
+            props.model_name().as_deref(),
+            Some("translategemma-4b-it-Q4_K_M")
+        );
+        assert_eq!(props.default_generation_settings.n_ctx, 8192);
+    }
+
+    #[tokio::test]
+    async fn test_completion() {
+        let (url, request) = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/event-stream\r\n\
+             Connection: close\r\n\r\n\
+             data: {\"content\":\"你好\",\"stop\":false,\
+             \"completion_probabilities\":[{\"id\":1,\"token\":\"你好\",\"logprob\":-0.1,\
+             \"top_logprobs\":[{\"id\":1,\"token\":\"你好\",\"logprob\":-0.1}]}]}\n\n\
+             data: {\"content\":\"，世界\",\"stop\":false}\n\n\
+             data: {\"content\":\"\",\"stop\":true,\"tokens_predicted\":2}\n\n",
+        )
+        .await;
+
+        let completion = CompletionRequest::builder()
+            .prompt("Hello, world!")
+            .cache_prompt(true)
+            .n_probs(1)
+            .build();
+
+        let chunks = Client::new(url)
+            .completion(completion)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| chunk.content.as_str())
+                .collect::<Vec<_>>(),
+            ["你好", "，世界", ""]
+        );
+        assert_eq!(chunks[0].completion_probabilities[0].logprob, -0.1);
+        assert!(chunks[2].stop);
+
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /completion "), "{request}");
+        assert!(request.contains("\"cache_prompt\":true"), "{request}");
+        assert!(request.contains("\"n_probs\":1"), "{request}");
+        assert!(request.contains("\"stream\":true"), "{request}");
+    }
+
+    #[tokio::test]
+    async fn test_loading() {
+        let (url, _) = respond_once(
+            "HTTP/1.1 503 Service Unavailable\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"error\":{\"code\":503,\"message\":\"Loading model\",\"type\":\"unavailable_error\"}}",
+        )
+        .await;
+
+        let err = Client::new(url).props().await.unwrap_err();
+        assert!(
+            matches!(&err, Error::Status { message, .. } if message == "Loading model"),
+            "{err:?}"
+        );
+    }
+}
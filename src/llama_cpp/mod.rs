@@ -0,0 +1,6 @@
+#[allow(clippy::module_inception)]
+mod llama_cpp;
+mod types;
+
+pub use llama_cpp::*;
+pub use types::*;
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use std::path::Path;
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompletionRequest {
+    /// The raw prompt. No chat template is applied.
+    pub prompt: String,
+    pub stream: Option<bool>,
+    /// The maximum number of tokens to generate, or -1 for no limit.
+    pub n_predict: Option<i64>,
+    pub temperature: Option<f64>,
+    pub top_k: Option<u32>,
+    pub top_p: Option<f64>,
+    pub seed: Option<i64>,
+    pub repeat_penalty: Option<f64>,
+    pub stop: Option<Vec<String>>,
+    /// Reuses the KV cache of the previous request for their common prefix, so that the
+    /// instructions are not evaluated again for every translation.
+    pub cache_prompt: Option<bool>,
+    /// Returns the probabilities of the N most likely tokens for each generated token.
+    pub n_probs: Option<u32>,
+    /// A GBNF grammar the output must follow.
+    pub grammar: Option<String>,
+}
+
+impl CompletionRequest {
+    pub fn builder() -> CompletionRequestBuilder {
+        CompletionRequestBuilder(Default::default())
+    }
+}
+
+pub struct CompletionRequestBuilder(CompletionRequest);
+
+#[allow(dead_code)]
+impl CompletionRequestBuilder {
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.0.prompt = prompt.into();
+        self
+    }
+
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.0.stream = Some(stream);
+        self
+    }
+
+    pub fn n_predict(mut self, n_predict: i64) -> Self {
+        self.0.n_predict = Some(n_predict);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.0.temperature = Some(temperature);
+        self
+    }
+
+    pub fn top_k(mut self, top_k: u32) -> Self {
+        self.0.top_k = Some(top_k);
+        self
+    }
+
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        self.0.top_p = Some(top_p);
+        self
+    }
+
+    pub fn seed(mut self, seed: i64) -> Self {
+        self.0.seed = Some(seed);
+        self
+    }
+
+    pub fn repeat_penalty(mut self, repeat_penalty: f64) -> Self {
+        self.0.repeat_penalty = Some(repeat_penalty);
+        self
+    }
+
+    pub fn stop(mut self, stop: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.0.stop = Some(stop.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn cache_prompt(mut self, cache_prompt: bool) -> Self {
+        self.0.cache_prompt = Some(cache_prompt);
+        self
+    }
+
+    pub fn n_probs(mut self, n_probs: u32) -> Self {
+        self.0.n_probs = Some(n_probs);
+        self
+    }
+
+    pub fn grammar(mut self, grammar: impl Into<String>) -> Self {
+        self.0.grammar = Some(grammar.into());
+        self
+    }
+
+    pub fn build(self) -> CompletionRequest {
+        self.0
+    }
+}
+
+/// One server-sent event of a streamed completion.
+#[derive(Debug, Deserialize)]
+pub struct CompletionChunk {
+    #[serde(default)]
+    pub content: String,
+    /// Set on the last chunk.
+    #[serde(default)]
+    pub stop: bool,
+    /// Present when `n_probs` is set.
+    #[serde(default)]
+    pub completion_probabilities: Vec<TokenProbabilities>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenProbabilities {
+    pub token: String,
+    pub logprob: f64,
+    /// The most likely candidates for this position, the chosen token included.
+    #[serde(default)]
+    pub top_logprobs: Vec<TokenLogprob>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+}
+
+/// The server properties returned by `/props`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Props {
+    #[serde(default)]
+    pub model_path: String,
+    pub default_generation_settings: GenerationSettings,
+    #[serde(default)]
+    pub total_slots: u32,
+    pub build_info: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerationSettings {
+    /// The context size of each slot, in tokens.
+    pub n_ctx: u64,
+}
+
+impl Props {
+    /// Returns the name of the loaded model, taken from its file name.
+    pub fn model_name(&self) -> Option<String> {
+        Path::new(&self.model_path)
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+}
@@ -5,40 +5,85 @@ extern crate rust_i18n;
 
 mod about;
 mod assets;
+mod backend;
+mod backoff;
 mod config;
+mod connection_settings;
 mod editor;
 mod error;
+mod fake;
+mod glossary;
+mod glossary_dialog;
+mod http;
 mod input_editor;
 mod language;
+mod language_detection;
+mod language_pair;
 mod language_selector;
+mod llama_cpp;
 mod locale_selector;
+mod masking;
+mod masking_dialog;
 mod menu;
+mod model_filter;
+mod model_filter_dialog;
+mod model_info;
+mod model_preset;
+mod model_preset_dialog;
+mod model_pull;
 mod ollama;
+mod ollama_host;
 mod ollama_service;
+mod ollama_supervisor;
+mod openai;
 mod output_editor;
 mod prompt;
+mod prompt_template;
+mod prompt_template_dialog;
+mod secrets;
 mod status_bar;
+mod translation_style;
+mod translation_style_dialog;
 
 use crate::about::open_about_dialog;
 use crate::assets::{Assets, Icons};
+use crate::backend::translate_with_failover;
 use crate::config::{Config, ConfigEvent};
-use crate::error::show_io_error;
+use crate::connection_settings::open_connection_settings_dialog;
+use crate::error::{describe_ollama_error, show_io_error};
+use crate::glossary::check_output;
+use crate::glossary_dialog::open_glossary_dialog;
 use crate::input_editor::InputEditor;
+use crate::language::{Direction, Language};
+use crate::language_detection::detect_language;
+use crate::language_pair::{LanguagePair, MAX_RECENT_PAIRS};
 use crate::language_selector::LanguageSelector;
 use crate::locale_selector::{ChangeLocale, LocaleSelector};
-use crate::ollama::{generate, GenerateRequest};
+use crate::masking::MaskReport;
+use crate::masking_dialog::open_masking_dialog;
+use crate::model_filter::is_recommended;
+use crate::model_filter_dialog::open_model_filter_dialog;
+use crate::model_info::open_model_info_dialog;
+use crate::model_preset_dialog::open_model_preset_dialog;
+use crate::model_pull::open_model_pull_dialog;
+use crate::ollama::{self, ServeOptions};
+use crate::ollama_host::open_ollama_host_dialog;
 use crate::ollama_service::{OllamaService, OllamaServiceEvent};
-use crate::output_editor::OutputEditor;
+use crate::ollama_supervisor::{open_ollama_logs_dialog, OllamaSupervisor};
+use crate::output_editor::{OutputEditor, OutputEditorEvent};
 use crate::prompt::Prompt;
+use crate::prompt_template_dialog::open_prompt_template_dialog;
 use crate::status_bar::StatusBar;
+use crate::translation_style_dialog::{open_translation_style_dialog, style_summary};
 use futures_util::StreamExt;
 use gpui::{
-    actions, div, prelude::*, px, size, Action, App, Application, Bounds,
-    ClickEvent, Entity, Focusable, PathPromptOptions, Task, Window, WindowBounds, WindowOptions,
+    actions, div, prelude::*, px, size, Action, App, Application, Bounds, ClickEvent, Corner,
+    Entity, Focusable, KeyBinding, PathPromptOptions, Task, Window, WindowBounds, WindowOptions,
 };
 use gpui_component::button::{Button, ButtonVariants};
 use gpui_component::input::{InputEvent, InputState};
-use gpui_component::menu::AppMenuBar;
+use gpui_component::menu::{AppMenuBar, DropdownMenu};
+use gpui_component::notification::Notification;
 use gpui_component::{gray_600, Root, TitleBar, WindowExt};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -48,7 +93,33 @@ use std::time::Duration;
 
 i18n!("locales", fallback = "en");
 
-actions!([About, Repository, Open, SaveInput, SaveOutput, Exit]);
+/// How long the input has to stay unchanged after a translation before its language pair is
+/// recorded as recent.
+const RECORD_PAIR_DELAY: Duration = Duration::from_secs(2);
+
+actions!([
+    About,
+    Repository,
+    Open,
+    SaveInput,
+    SaveOutput,
+    Exit,
+    OllamaHost,
+    PullModel,
+    ShowModelInfo,
+    EditModelPreset,
+    EditPromptTemplate,
+    EditTranslationStyle,
+    EditGlossary,
+    EditMasking,
+    ToggleFavoritePair,
+    TogglePreloadModel,
+    ToggleShowAllModels,
+    EditModelFilter,
+    Refresh,
+    ToggleManageOllama,
+    ShowOllamaLogs
+]);
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Action)]
 struct ChangeModel {
@@ -61,9 +132,72 @@ impl ChangeModel {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Action)]
+struct ChangePollInterval {
+    secs: u64,
+}
+
+impl ChangePollInterval {
+    fn new(secs: u64) -> Self {
+        ChangePollInterval { secs }
+    }
+}
+
+/// Switches to a recent language pair that isn't a favorite, by its place in the quick pairs
+/// menu. Using a recent pair again doesn't move it, so a shortcut keeps switching to the same pair
+/// until it drops out of the recent pairs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Action)]
+struct SwitchRecentPair {
+    index: usize,
+}
+
+impl SwitchRecentPair {
+    fn new(index: usize) -> Self {
+        SwitchRecentPair { index }
+    }
+}
+
+/// Switches to a favorite language pair, by its place among the favorites. Recording recent
+/// pairs doesn't reorder the favorites, so a shortcut keeps switching to the same pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Action)]
+struct SwitchFavoritePair {
+    index: usize,
+}
+
+impl SwitchFavoritePair {
+    fn new(index: usize) -> Self {
+        SwitchFavoritePair { index }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Action)]
+struct ConnectionSettings {
+    base_url: String,
+}
+
+impl ConnectionSettings {
+    fn new(base_url: impl Into<String>) -> Self {
+        ConnectionSettings {
+            base_url: base_url.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Action)]
+struct UnloadModel {
+    name: String,
+}
+
+impl UnloadModel {
+    fn new(name: impl Into<String>) -> Self {
+        UnloadModel { name: name.into() }
+    }
+}
+
 struct TranslateApp {
     config: Entity<Config>,
     ollama_service: Entity<OllamaService>,
+    ollama_supervisor: Entity<OllamaSupervisor>,
 
     locale_selector: Entity<LocaleSelector>,
 
@@ -76,6 +210,10 @@ struct TranslateApp {
     output_editor: Entity<OutputEditor>,
 
     generate: Option<Task<anyhow::Result<()>>>,
+    /// Records the language pair of the finished translation, unless the input changes first.
+    record_pair: Option<Task<()>>,
+    /// The model and prompt of the latest translation, for retrying it.
+    last_translation: Option<(String, Prompt)>,
 }
 
 impl TranslateApp {
@@ -86,13 +224,24 @@ impl TranslateApp {
         let input_state = input_editor.read(cx).state.clone();
         cx.subscribe_in(&input_state, window, Self::on_input_event)
             .detach();
+        cx.subscribe_in(&output_editor, window, Self::on_output_event)
+            .detach();
 
         let locale_selector =
             cx.new(|cx| LocaleSelector::new(input_editor.focus_handle(cx).clone()));
 
+        let config = Self::setup_config(window, cx);
+        let ollama_service = Self::setup_ollama_service(&config, cx);
+        let ollama_supervisor = Self::setup_ollama_supervisor(cx);
+
+        cx.defer_in(window, |this, window, cx| {
+            this.start_ollama_server(window, cx);
+        });
+
         TranslateApp {
-            config: Self::setup_config(window, cx),
-            ollama_service: Self::setup_ollama_service(cx),
+            config,
+            ollama_service,
+            ollama_supervisor,
             locale_selector,
             source_language_selector: Self::setup_source_language_selector(window, cx),
             target_language_selector: Self::setup_target_language_selector(window, cx),
@@ -100,26 +249,42 @@ impl TranslateApp {
             input_editor,
             output_editor,
             generate: None,
+            record_pair: None,
+            last_translation: None,
         }
     }
 
-    fn setup_ollama_service(cx: &mut Context<Self>) -> Entity<OllamaService> {
+    fn setup_ollama_service(
+        config: &Entity<Config>,
+        cx: &mut Context<Self>,
+    ) -> Entity<OllamaService> {
+        let endpoints = config.read(cx).endpoints();
+        let model_filter = config.read(cx).model_filter().clone();
+        let poll_interval = config.read(cx).poll_interval();
+
         let ollama_service = cx.new(|cx| {
-            let mut service = OllamaService::new();
+            let mut service = OllamaService::new(endpoints, model_filter, poll_interval);
             service.start_polling(cx);
             service
         });
 
         cx.subscribe(&ollama_service, |this, ollama, event, cx| match event {
             OllamaServiceEvent::ModelsChanged => {
+                let models = &ollama.read(cx).models;
+
+                // Prefer TranslateGemma when other models are listed too.
                 if this.config.read(cx).model().is_none()
-                    && let Some(model) = ollama.read(cx).models.first().cloned()
+                    && let Some(model) = models
+                        .iter()
+                        .find(|model| is_recommended(&model.name))
+                        .or_else(|| models.first())
+                        .map(|model| model.name.clone())
                 {
                     this.config.update(cx, |this, cx| this.set_model(model, cx));
                 }
                 cx.notify();
             }
-            OllamaServiceEvent::VersionChanged => {
+            OllamaServiceEvent::ConnectionChanged | OllamaServiceEvent::RunningModelsChanged => {
                 cx.notify();
             }
         })
@@ -128,13 +293,119 @@ impl TranslateApp {
         ollama_service
     }
 
+    fn setup_ollama_supervisor(cx: &mut Context<Self>) -> Entity<OllamaSupervisor> {
+        let ollama_supervisor = cx.new(|_| OllamaSupervisor::new());
+
+        // Waits for `ollama serve` to exit, so it is not left running after the app.
+        cx.on_app_quit(|this, cx| {
+            this.ollama_supervisor
+                .update(cx, |this, cx| this.stop_and_wait(cx))
+        })
+        .detach();
+
+        ollama_supervisor
+    }
+
+    /// Points the Ollama service at the configured servers with their connection settings.
+    fn connect_ollama(&mut self, cx: &mut Context<Self>) {
+        let endpoints = self.config.read(cx).endpoints();
+
+        self.ollama_service.update(cx, |this, cx| {
+            this.set_endpoints(endpoints, cx);
+        });
+    }
+
+    /// Starts `ollama serve` for a local host if the app is configured to manage Ollama, and
+    /// stops it otherwise.
+    fn start_ollama_server(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let config = self.config.read(cx);
+        // Only an Ollama server can be started.
+        let Some(client) = self
+            .ollama_service
+            .read(cx)
+            .ollama_client()
+            .filter(|_| config.manage_ollama())
+        else {
+            self.ollama_supervisor.update(cx, |this, cx| {
+                this.stop(cx);
+            });
+            return;
+        };
+
+        let base_url = config.ollama_base_url();
+        let is_local = match base_url.host() {
+            Some(url::Host::Domain(domain)) => domain == "localhost",
+            Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        };
+
+        if !is_local {
+            window.push_notification(
+                Notification::warning(t!("ollama-serve.remote").to_string()),
+                cx,
+            );
+            return;
+        }
+
+        let Some(binary) = ollama::find_binary(config.ollama_path().map(PathBuf::as_path)) else {
+            window.push_notification(
+                Notification::error(t!("ollama-serve.not-found").to_string()),
+                cx,
+            );
+            return;
+        };
+
+        let options = ServeOptions {
+            binary,
+            host: base_url
+                .host_str()
+                .zip(base_url.port_or_known_default())
+                .map(|(host, port)| format!("{host}:{port}")),
+            models_dir: config.ollama_models_dir().cloned(),
+        };
+
+        self.ollama_supervisor.update(cx, |this, cx| {
+            this.start(client, options, cx);
+        });
+    }
+
     fn update_menu_bar(&mut self, cx: &mut Context<Self>) {
+        let ollama_service = self.ollama_service.read(cx);
+        // Where a model lives only matters when there are several servers.
+        let several_endpoints = ollama_service.endpoints.len() > 1;
+
+        let models = ollama_service
+            .models
+            .iter()
+            .map(|model| {
+                let locations = if several_endpoints {
+                    ollama_service.locations(&model.name)
+                } else {
+                    Vec::new()
+                };
+
+                (model.name.clone(), locations)
+            })
+            .collect::<Vec<_>>();
+        let endpoints = ollama_service
+            .endpoints
+            .iter()
+            .map(|endpoint| (endpoint.label(), endpoint.base_url.to_string()))
+            .collect();
+
         cx.set_menus(vec![
             menu::file_menu(),
             menu::model_menu(
-                &self.ollama_service.read(cx).models,
+                models,
                 self.config.read(cx).model(),
+                self.config.read(cx).model_filter().show_all,
+                self.config.read(cx).preload_model(),
+                self.config.read(cx).poll_interval(),
+                self.config.read(cx).manage_ollama(),
+                endpoints,
             ),
+            menu::translation_menu(),
             menu::help_menu(),
         ]);
 
@@ -147,7 +418,7 @@ impl TranslateApp {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Entity<LanguageSelector> {
-        let source_language_selector = cx.new(|cx| LanguageSelector::new(window, cx));
+        let source_language_selector = cx.new(|cx| LanguageSelector::with_detection(window, cx));
 
         cx.subscribe(&source_language_selector, |this, _, event, cx| {
             if let Some(language) = event.value() {
@@ -239,6 +510,10 @@ impl TranslateApp {
             ConfigEvent::SwapLanguages {
                 source_language,
                 target_language,
+            }
+            | ConfigEvent::LanguagePairChange {
+                source_language,
+                target_language,
             } => {
                 if let (Some(source_language), Some(target_language)) =
                     (source_language, target_language)
@@ -256,8 +531,46 @@ impl TranslateApp {
                     this.translate(window, cx);
                 }
             }
+            ConfigEvent::QuickPairsChange => {
+                cx.notify();
+            }
             ConfigEvent::ModelChange => {
+                this.preload_model(cx);
+                this.translate(window, cx);
+                cx.notify();
+            }
+            ConfigEvent::PresetChange | ConfigEvent::PromptChange => {
                 this.translate(window, cx);
+            }
+            ConfigEvent::ModelFilterChange => {
+                let model_filter = this.config.read(cx).model_filter().clone();
+
+                this.ollama_service.update(cx, |this, cx| {
+                    this.set_model_filter(model_filter, cx);
+                });
+                cx.notify();
+            }
+            ConfigEvent::PollIntervalChange => {
+                let poll_interval = this.config.read(cx).poll_interval();
+
+                this.ollama_service.update(cx, |this, cx| {
+                    this.set_poll_interval(poll_interval, cx);
+                });
+                cx.notify();
+            }
+            ConfigEvent::PreloadModelChange => {
+                this.preload_model(cx);
+                cx.notify();
+            }
+            ConfigEvent::OllamaHostChange => {
+                this.connect_ollama(cx);
+                this.start_ollama_server(window, cx);
+            }
+            ConfigEvent::ConnectionOptionsChange => {
+                this.connect_ollama(cx);
+            }
+            ConfigEvent::ManageOllamaChange => {
+                this.start_ollama_server(window, cx);
                 cx.notify();
             }
             _ => {}
@@ -275,80 +588,256 @@ impl TranslateApp {
         cx: &mut Context<Self>,
     ) {
         if matches!(event, InputEvent::Change) {
+            self.detect_language(window, cx);
             self.translate(window, cx);
         }
     }
 
-    fn prompt(&mut self, cx: &App) -> Option<Prompt> {
+    /// Guesses the language of the input for the Detect language entry of the source selector.
+    fn detect_language(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let detection = detect_language(&self.input_editor.read(cx).text(cx));
+
+        self.source_language_selector.update(cx, |this, cx| {
+            this.set_detection(detection, window, cx);
+        });
+    }
+
+    /// Aligns the input and output to the direction of the source and target languages. Every
+    /// change of either language, including a new detection, leads to a translation, which calls
+    /// this first.
+    fn update_text_direction(&mut self, cx: &mut Context<Self>) {
         let source_language = self.source_language_selector.read(cx).selected_language(cx);
         let target_language = self.target_language_selector.read(cx).selected_language(cx);
 
-        if let (Some(source_language), Some(target_language)) = (source_language, target_language)
+        self.input_editor.update(cx, |this, cx| {
+            this.set_direction(
+                source_language.map_or_else(Direction::default, |language| language.direction),
+                cx,
+            );
+        });
+        self.output_editor.update(cx, |this, cx| {
+            this.set_direction(
+                target_language.map_or_else(Direction::default, |language| language.direction),
+                cx,
+            );
+        });
+    }
+
+    /// Returns the selected source and target languages.
+    fn language_pair(&self, cx: &App) -> Option<(Language, Language)> {
+        let source_language = self.source_language_selector.read(cx).selected_language(cx);
+        let target_language = self.target_language_selector.read(cx).selected_language(cx);
+
+        source_language.zip(target_language)
+    }
+
+    fn prompt(&mut self, cx: &App) -> Option<Prompt> {
+        if let Some((source_language, target_language)) = self.language_pair(cx)
             && !self.input_editor.read(cx).is_empty(cx)
         {
-            Some(Prompt::new(
-                source_language,
-                target_language,
-                self.input_editor.read(cx).text(cx),
-            ))
+            let config = self.config.read(cx);
+            let template = config.prompt_template(source_language.code, target_language.code);
+            let settings = config.pair_settings(source_language.code, target_language.code);
+            let text = self.input_editor.read(cx).text(cx);
+            let glossary = config
+                .glossary(source_language.code, target_language.code)
+                .matches(&text);
+
+            Some(
+                Prompt::new(source_language, target_language, text)
+                    .template(template)
+                    .glossary(glossary)
+                    .context(settings.context.unwrap_or_default())
+                    .style(settings.style),
+            )
         } else {
             None
         }
     }
 
+    fn on_output_event(
+        &mut self,
+        _: &Entity<OutputEditor>,
+        event: &OutputEditorEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            OutputEditorEvent::Retry => self.retry(window, cx),
+        }
+    }
+
     fn translate(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_text_direction(cx);
+
         if let Some(model) = self.config.read(cx).model().cloned()
             && let Some(prompt) = self.prompt(cx)
         {
-            let output_editor = self.output_editor.clone();
+            self.translate_prompt(model, prompt, window, cx);
+        }
+    }
+
+    fn retry(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((model, prompt)) = self.last_translation.clone() {
+            self.translate_prompt(model, prompt, window, cx);
+        }
+    }
 
-            let has_active_task = self.generate.as_ref().is_some_and(|task| !task.is_ready());
-            self.generate = None;
+    fn translate_prompt(
+        &mut self,
+        model: String,
+        prompt: Prompt,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.last_translation = Some((model.clone(), prompt.clone()));
+
+        let output_editor = self.output_editor.clone();
+        let backends = self.ollama_service.read(cx).backends_for(&model);
+        let preset = self.config.read(cx).preset(&model);
+
+        // Placeholders and markup are sent as tokens, and restored as the output streams in.
+        let masked = self.config.read(cx).masking().mask(&prompt.text);
+        let mut unmasker = masked.unmasker();
+        let request = Prompt {
+            text: masked.text,
+            ..prompt.clone()
+        }
+        .masked(!masked.tokens.is_empty());
 
-            self.generate = Some(cx.spawn_in(window, async move |_, window| {
-                if has_active_task {
-                    output_editor.update_in(window, |this, window, cx| {
-                        this.wait_for_input(window, cx);
-                    })?;
+        output_editor.update(cx, |this, cx| {
+            this.clear_error(cx);
+            this.set_missing_terms(Vec::new(), cx);
+            this.set_mask_report(MaskReport::default(), cx);
+        });
 
-                    window
-                        .background_executor()
-                        .timer(Duration::from_millis(500))
-                        .await;
+        let has_active_task = self.generate.as_ref().is_some_and(|task| !task.is_ready());
+        // Dropping the previous task aborts its request, so Ollama stops generating.
+        self.generate = None;
+        self.record_pair = None;
+
+        self.generate = Some(cx.spawn_in(window, async move |this, window| {
+            if has_active_task {
+                output_editor.update_in(window, |this, window, cx| {
+                    this.wait_for_input(window, cx);
+                })?;
+
+                window
+                    .background_executor()
+                    .timer(Duration::from_millis(500))
+                    .await;
+
+                output_editor.update_in(window, |this, window, cx| {
+                    this.translate_in_progress(window, cx);
+                })?;
+            }
+
+            let result = translate_with_failover(&backends, &model, &request, &preset).await;
 
-                    output_editor.update_in(window, |this, window, cx| {
-                        this.translate_in_progress(window, cx);
-                    })?;
+            let mut result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    return this.update_in(window, |this, window, cx| {
+                        this.on_translate_error(err, false, window, cx);
+                    });
                 }
+            };
 
-                let req = GenerateRequest::builder()
-                    .model(model)
-                    .stream(true)
-                    .prompt(prompt.to_string())
-                    .build();
+            let mut first = true;
 
-                let mut result = generate(req).await?;
+            while let Some(item) = result.next().await {
+                let response = match item {
+                    Ok(chunk) => chunk,
+                    Err(err) => {
+                        let has_output = !first;
 
-                if let Some(item) = result.next().await {
-                    let response = item?.response;
+                        return this.update_in(window, |this, window, cx| {
+                            this.on_translate_error(err, has_output, window, cx);
+                        });
+                    }
+                };
+
+                let response = unmasker.push(&response);
 
-                    output_editor.update_in(window, |this, window, cx| {
+                output_editor.update_in(window, |this, window, cx| {
+                    if first {
                         this.reset(window, cx);
-                        this.append(response, window, cx);
-                    })?;
+                    }
+                    this.append(response, window, cx);
+                })?;
+
+                first = false;
+            }
+
+            let rest = unmasker.finish();
+
+            // Point out the glossary terms and protected text the model did not keep.
+            output_editor.update_in(window, |this, window, cx| {
+                if !rest.is_empty() {
+                    this.append(rest, window, cx);
                 }
 
-                while let Some(item) = result.next().await {
-                    let response = item?.response;
+                let output = this.text(cx);
+                let missing = check_output(&prompt.glossary, &prompt.text, &output);
+
+                this.set_missing_terms(missing, cx);
+                this.set_mask_report(unmasker.report(), cx);
+            })?;
+
+            this.update_in(window, |this, window, cx| {
+                let pair =
+                    LanguagePair::new(prompt.source_language.code, prompt.target_language.code);
+                this.record_language_pair(pair, window, cx);
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        }));
+    }
 
-                    output_editor.update_in(window, |this, window, cx| {
-                        this.append(response, window, cx);
-                    })?;
+    /// Records the language pair as recent once the input has settled. A new translation drops
+    /// the task first, so pairs guessed from half-typed input are not recorded, nor are weak
+    /// guesses of Detect language.
+    fn record_language_pair(
+        &mut self,
+        pair: LanguagePair,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.record_pair = Some(cx.spawn_in(window, async move |this, window| {
+            window.background_executor().timer(RECORD_PAIR_DELAY).await;
+
+            this.update(window, |this, cx| {
+                if this.source_language_selector.read(cx).is_guessing(cx) {
+                    return;
                 }
 
-                Ok::<_, anyhow::Error>(())
-            }));
-        }
+                this.config.update(cx, |this, cx| {
+                    this.record_language_pair(pair, cx);
+                });
+            })
+            .ok();
+        }));
+    }
+
+    /// Shows the error in the output. Without any output for this translation yet, the output of
+    /// the previous one is cleared, so it is not mistaken for a translation of the current text.
+    fn on_translate_error(
+        &mut self,
+        error: ollama::Error,
+        has_output: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (summary, _) = describe_ollama_error(&error);
+
+        window.push_notification(Notification::error(error.to_string()).title(summary), cx);
+
+        self.output_editor.update(cx, |this, cx| {
+            if !has_output {
+                this.reset(window, cx);
+            }
+            this.show_error(&error, window, cx);
+        });
     }
 
     fn on_action_change_locale(
@@ -373,6 +862,290 @@ impl TranslateApp {
         });
     }
 
+    /// Loads the selected model into memory if preloading is enabled.
+    fn preload_model(&mut self, cx: &mut Context<Self>) {
+        let config = self.config.read(cx);
+
+        if config.preload_model()
+            && let Some(model) = config.model().cloned()
+        {
+            // Failures show up when translating, so they are ignored here.
+            self.ollama_service
+                .update(cx, |this, cx| this.preload(model, cx))
+                .detach();
+        }
+    }
+
+    fn on_action_toggle_manage_ollama(
+        &mut self,
+        _: &ToggleManageOllama,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.config.update(cx, |this, cx| {
+            this.toggle_manage_ollama(cx);
+        });
+    }
+
+    fn on_action_show_ollama_logs(
+        &mut self,
+        _: &ShowOllamaLogs,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        open_ollama_logs_dialog(self.ollama_supervisor.clone(), window, cx);
+    }
+
+    fn on_action_refresh(&mut self, _: &Refresh, _: &mut Window, cx: &mut Context<Self>) {
+        self.ollama_service.update(cx, |this, cx| {
+            this.refresh(cx);
+        });
+    }
+
+    fn on_action_change_poll_interval(
+        &mut self,
+        change_poll_interval: &ChangePollInterval,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.config.update(cx, |this, cx| {
+            this.set_poll_interval(Duration::from_secs(change_poll_interval.secs), cx);
+        });
+    }
+
+    fn on_action_switch_recent_pair(
+        &mut self,
+        switch_recent_pair: &SwitchRecentPair,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let pair = self
+            .config
+            .read(cx)
+            .quick_pairs()
+            .other_recent()
+            .nth(switch_recent_pair.index)
+            .cloned();
+
+        if let Some(pair) = pair {
+            self.config.update(cx, |this, cx| {
+                this.set_language_pair(&pair, cx);
+            });
+        }
+    }
+
+    fn on_action_switch_favorite_pair(
+        &mut self,
+        switch_favorite_pair: &SwitchFavoritePair,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let pair = self
+            .config
+            .read(cx)
+            .quick_pairs()
+            .favorites
+            .get(switch_favorite_pair.index)
+            .cloned();
+
+        if let Some(pair) = pair {
+            self.config.update(cx, |this, cx| {
+                this.set_language_pair(&pair, cx);
+            });
+        }
+    }
+
+    fn on_action_toggle_favorite_pair(
+        &mut self,
+        _: &ToggleFavoritePair,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some((source_language, target_language)) = self.language_pair(cx) {
+            self.config.update(cx, |this, cx| {
+                let pair = LanguagePair::new(source_language.code, target_language.code);
+                this.toggle_favorite_pair(pair, cx);
+            });
+        }
+    }
+
+    fn on_action_toggle_show_all_models(
+        &mut self,
+        _: &ToggleShowAllModels,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.config.update(cx, |this, cx| {
+            this.toggle_show_all_models(cx);
+        });
+    }
+
+    fn on_action_edit_model_filter(
+        &mut self,
+        _: &EditModelFilter,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        open_model_filter_dialog(self.config.clone(), window, cx);
+    }
+
+    fn on_action_toggle_preload_model(
+        &mut self,
+        _: &TogglePreloadModel,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.config.update(cx, |this, cx| {
+            this.toggle_preload_model(cx);
+        });
+    }
+
+    fn on_action_unload_model(
+        &mut self,
+        unload_model: &UnloadModel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let task = self
+            .ollama_service
+            .update(cx, |this, cx| this.unload(&unload_model.name, cx));
+
+        cx.spawn_in(window, async move |_, window| {
+            if let Err(err) = task.await {
+                window
+                    .update(|window, cx| {
+                        let (summary, _) = describe_ollama_error(&err);
+
+                        window.push_notification(
+                            Notification::error(err.to_string()).title(summary),
+                            cx,
+                        );
+                    })
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn on_action_show_model_info(
+        &mut self,
+        _: &ShowModelInfo,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        open_model_info_dialog(self.ollama_service.clone(), self.config.clone(), window, cx);
+    }
+
+    fn on_action_edit_model_preset(
+        &mut self,
+        _: &EditModelPreset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(model) = self.config.read(cx).model().cloned() {
+            open_model_preset_dialog(self.config.clone(), model, window, cx);
+        }
+    }
+
+    fn on_action_edit_prompt_template(
+        &mut self,
+        _: &EditPromptTemplate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((source_language, target_language)) = self.language_pair(cx) else {
+            return;
+        };
+
+        let sample = if self.input_editor.read(cx).is_empty(cx) {
+            t!("prompt-template.sample").into_owned()
+        } else {
+            self.input_editor.read(cx).text(cx).to_string()
+        };
+
+        open_prompt_template_dialog(
+            self.config.clone(),
+            source_language,
+            target_language,
+            sample,
+            window,
+            cx,
+        );
+    }
+
+    fn on_action_edit_translation_style(
+        &mut self,
+        _: &EditTranslationStyle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some((source_language, target_language)) = self.language_pair(cx) {
+            open_translation_style_dialog(
+                self.config.clone(),
+                source_language,
+                target_language,
+                window,
+                cx,
+            );
+        }
+    }
+
+    fn on_action_edit_glossary(
+        &mut self,
+        _: &EditGlossary,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some((source_language, target_language)) = self.language_pair(cx) {
+            open_glossary_dialog(
+                self.config.clone(),
+                source_language,
+                target_language,
+                window,
+                cx,
+            );
+        }
+    }
+
+    fn on_action_edit_masking(
+        &mut self,
+        _: &EditMasking,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let sample = if self.input_editor.read(cx).is_empty(cx) {
+            t!("masking.sample").into_owned()
+        } else {
+            self.input_editor.read(cx).text(cx).to_string()
+        };
+
+        open_masking_dialog(self.config.clone(), sample, window, cx);
+    }
+
+    fn on_action_pull_model(&mut self, _: &PullModel, window: &mut Window, cx: &mut Context<Self>) {
+        open_model_pull_dialog(self.ollama_service.clone(), window, cx);
+    }
+
+    fn on_action_ollama_host(
+        &mut self,
+        _: &OllamaHost,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        open_ollama_host_dialog(self.config.clone(), window, cx);
+    }
+
+    fn on_action_connection_settings(
+        &mut self,
+        connection_settings: &ConnectionSettings,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Ok(base_url) = connection_settings.base_url.parse() {
+            open_connection_settings_dialog(self.config.clone(), base_url, window, cx);
+        }
+    }
+
     fn on_action_repository(&mut self, _: &Repository, _: &mut Window, cx: &mut Context<Self>) {
         cx.open_url("https://github.com/fhluo/translate-gemma-desktop")
     }
@@ -382,11 +1155,102 @@ impl TranslateApp {
     }
 
     fn on_click_swap_languages(&mut self, _: &ClickEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let source_language_selector = self.source_language_selector.read(cx);
+
+        // The target cannot be detected, so the detected language takes its place.
+        let detected = if source_language_selector.is_detecting(cx) {
+            match source_language_selector.selected_language(cx) {
+                Some(language) => Some(language),
+                None => return,
+            }
+        } else {
+            None
+        };
+
         self.config.update(cx, |this, cx| {
+            if let Some(language) = detected {
+                this.set_source_language(language.code, cx);
+            }
+
             this.swap_languages(cx);
         })
     }
 
+    /// Lists the favorite language pairs, the first nine with a shortcut each, followed by the
+    /// recent ones, each with a shortcut of its own.
+    fn quick_pairs_button(&self, cx: &App) -> impl IntoElement {
+        let source_language_selector = self.source_language_selector.read(cx);
+        let target_language_selector = self.target_language_selector.read(cx);
+        let quick_pairs = self.config.read(cx).quick_pairs();
+
+        let label = |pair: &LanguagePair| {
+            let source = source_language_selector
+                .display_name(&pair.source)
+                .unwrap_or_else(|| pair.source.clone().into());
+            let target = target_language_selector
+                .display_name(&pair.target)
+                .unwrap_or_else(|| pair.target.clone().into());
+
+            format!("{source} → {target}")
+        };
+
+        let favorites = quick_pairs
+            .favorites
+            .iter()
+            .enumerate()
+            .map(|(index, pair)| {
+                let action: Box<dyn Action> = Box::new(SwitchFavoritePair::new(index));
+                (label(pair), true, action)
+            });
+        let recent = quick_pairs.other_recent().enumerate().map(|(index, pair)| {
+            let action: Box<dyn Action> = Box::new(SwitchRecentPair::new(index));
+            (label(pair), false, action)
+        });
+        let pairs = favorites.chain(recent).collect::<Vec<_>>();
+        let is_favorite = self.language_pair(cx).map(|(source, target)| {
+            quick_pairs.is_favorite(&LanguagePair::new(source.code, target.code))
+        });
+        let focus_handle = self.input_editor.focus_handle(cx);
+
+        Button::new("quick-pairs-button")
+            .ghost()
+            .icon(Icons::Star)
+            .text_color(gray_600())
+            .tooltip(t!("quick-pairs"))
+            .dropdown_menu(move |mut menu, _, _| {
+                menu = menu.action_context(focus_handle.clone());
+
+                for (label, favorite, action) in &pairs {
+                    menu = menu.menu_with_check(label.clone(), *favorite, action.boxed_clone());
+                }
+
+                if let Some(is_favorite) = is_favorite {
+                    if !pairs.is_empty() {
+                        menu = menu.separator();
+                    }
+
+                    let label = if is_favorite {
+                        t!("quick-pairs.unpin")
+                    } else {
+                        t!("quick-pairs.pin")
+                    };
+                    menu = menu.menu(label, Box::new(ToggleFavoritePair));
+                }
+
+                menu
+            })
+            .anchor(Corner::TopLeft)
+    }
+
+    fn on_click_edit_translation_style(
+        &mut self,
+        _: &ClickEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.on_action_edit_translation_style(&EditTranslationStyle, window, cx);
+    }
+
     fn last_directory(&mut self, cx: &mut Context<Self>) -> PathBuf {
         self.config
             .read(cx)
@@ -505,6 +1369,26 @@ impl Render for TranslateApp {
             .on_action(cx.listener(Self::on_action_about))
             .on_action(cx.listener(Self::on_action_change_model))
             .on_action(cx.listener(Self::on_action_change_locale))
+            .on_action(cx.listener(Self::on_action_show_model_info))
+            .on_action(cx.listener(Self::on_action_edit_model_preset))
+            .on_action(cx.listener(Self::on_action_edit_prompt_template))
+            .on_action(cx.listener(Self::on_action_edit_translation_style))
+            .on_action(cx.listener(Self::on_action_edit_glossary))
+            .on_action(cx.listener(Self::on_action_edit_masking))
+            .on_action(cx.listener(Self::on_action_refresh))
+            .on_action(cx.listener(Self::on_action_toggle_manage_ollama))
+            .on_action(cx.listener(Self::on_action_show_ollama_logs))
+            .on_action(cx.listener(Self::on_action_change_poll_interval))
+            .on_action(cx.listener(Self::on_action_toggle_show_all_models))
+            .on_action(cx.listener(Self::on_action_switch_recent_pair))
+            .on_action(cx.listener(Self::on_action_switch_favorite_pair))
+            .on_action(cx.listener(Self::on_action_toggle_favorite_pair))
+            .on_action(cx.listener(Self::on_action_edit_model_filter))
+            .on_action(cx.listener(Self::on_action_toggle_preload_model))
+            .on_action(cx.listener(Self::on_action_unload_model))
+            .on_action(cx.listener(Self::on_action_pull_model))
+            .on_action(cx.listener(Self::on_action_ollama_host))
+            .on_action(cx.listener(Self::on_action_connection_settings))
             .on_action(cx.listener(Self::on_action_open))
             .on_action(cx.listener(Self::on_action_save_input))
             .on_action(cx.listener(Self::on_action_save_output))
@@ -548,7 +1432,22 @@ impl Render for TranslateApp {
                             .tooltip(t!("swap-languages"))
                             .on_click(cx.listener(Self::on_click_swap_languages)),
                     )
-                    .child(self.target_language_selector.clone()),
+                    .child(self.quick_pairs_button(cx))
+                    .child(self.target_language_selector.clone())
+                    .children(self.language_pair(cx).map(|(source, target)| {
+                        let style = self
+                            .config
+                            .read(cx)
+                            .pair_settings(source.code, target.code)
+                            .style;
+
+                        Button::new("style-button")
+                            .ghost()
+                            .label(style_summary(&style))
+                            .text_color(gray_600())
+                            .tooltip(t!("translation-style"))
+                            .on_click(cx.listener(Self::on_click_edit_translation_style))
+                    })),
             )
             .child(
                 div()
@@ -561,7 +1460,21 @@ impl Render for TranslateApp {
                     .child(self.input_editor.clone())
                     .child(self.output_editor.clone()),
             )
-            .child(StatusBar::new(self.ollama_service.read(cx).version.clone()))
+            .child(StatusBar::new(
+                self.ollama_service.read(cx).state(),
+                self.ollama_service.read(cx).last_error(),
+                self.ollama_service.read(cx).kind().name(),
+                self.ollama_service.read(cx).version(),
+                self.ollama_service
+                    .read(cx)
+                    .endpoints
+                    .iter()
+                    .map(|endpoint| (endpoint.label(), endpoint.state.clone()))
+                    .collect(),
+                self.ollama_service.read(cx).loaded_model(),
+                self.ollama_service.read(cx).running_models.clone(),
+                self.input_editor.focus_handle(cx),
+            ))
             .children(notification_layer)
             .children(dialog_layer)
     }
@@ -573,6 +1486,23 @@ fn main() -> anyhow::Result<()> {
     app.run(move |cx| {
         gpui_component::init(cx);
 
+        // Secondary-1 to secondary-9 switch to the first nine favorite language pairs.
+        cx.bind_keys((0..9).map(|index| {
+            KeyBinding::new(
+                &format!("secondary-{}", index + 1),
+                SwitchFavoritePair::new(index),
+                None,
+            )
+        }));
+        // Secondary-alt-1 onwards switch to the recent language pairs that aren't favorites.
+        cx.bind_keys((0..MAX_RECENT_PAIRS).map(|index| {
+            KeyBinding::new(
+                &format!("secondary-alt-{}", index + 1),
+                SwitchRecentPair::new(index),
+                None,
+            )
+        }));
+
         let bounds = Bounds::centered(None, size(px(1000.), px(625.)), cx);
 
         cx.spawn(async move |cx| {
@@ -0,0 +1,341 @@
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::mem;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Added to the prompt when the text has tokens, so the model keeps them.
+pub const MASKING_INSTRUCTION: &str = "The text contains markers such as ⟦1⟧ that stand for text which must not change. Keep every ⟦n⟧ marker exactly as written, once each, where it belongs in the translation.";
+
+/// Replaces the protected parts of the text, e.g. `⟦1⟧`.
+static TOKEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"⟦\s*(\d+)\s*⟧").unwrap());
+
+/// `{name}`, `{0}`, `${name}`, `%s`, `%1$d`, `%.2f` and `$1`.
+static FORMAT_SPECIFIER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\$?\{[\w.-]*\}|%(?:\d+\$)?[-+#0]*(?:\d+|\*)?(?:\.(?:\d+|\*))?(?:hh|h|ll|l|L|q|j|z|t)?[diouxXeEfFgGaAcsp@]|\$\d+",
+    )
+    .unwrap()
+});
+
+/// The start of an ICU MessageFormat argument with a type, e.g. `{count, plural,`.
+static ICU_ARGUMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\s*[\w-]+\s*,\s*\w+").unwrap());
+
+/// HTML and XML tags, comments and entities.
+static MARKUP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?s:<!--.*?-->)|</?[A-Za-z][\w:.-]*(?:\s[^<>]*)?/?>|&(?:[A-Za-z]+|#\d+|#x[0-9A-Fa-f]+);",
+    )
+    .unwrap()
+});
+
+/// Trailing punctuation is left out, as in `See https://example.com.`.
+static URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\b(?:https?|ftp)://[^\s<>"']*[^\s<>"'.,;:!?)\]]"#).unwrap());
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[\w.+-]+@[\w-]+(?:\.[\w-]+)+").unwrap());
+
+/// Which parts of the text are replaced with tokens before translating, so the model cannot
+/// translate or drop them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaskingRules {
+    pub enabled: bool,
+    /// `{name}`, `%s`, `%1$d` and `$1`.
+    pub format_specifiers: bool,
+    /// ICU MessageFormat arguments such as `{count, plural, one {# item} other {# items}}`, kept
+    /// whole including the text of their branches.
+    pub icu_messages: bool,
+    /// HTML and XML tags and entities.
+    pub markup: bool,
+    pub urls: bool,
+    pub emails: bool,
+    /// More regexes whose matches are protected, e.g. `\bJIRA-\d+\b`.
+    pub patterns: Vec<String>,
+}
+
+impl Default for MaskingRules {
+    fn default() -> Self {
+        MaskingRules {
+            enabled: true,
+            format_specifiers: true,
+            icu_messages: true,
+            markup: true,
+            urls: true,
+            emails: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// Returns the ranges of the ICU arguments with a type, up to their matching closing brace.
+fn icu_messages(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut from = 0;
+
+    while let Some(start) = ICU_ARGUMENT.find_at(text, from).map(|m| m.start()) {
+        let mut depth = 0;
+        let end = text[start..].char_indices().find_map(|(index, c)| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => return None,
+            }
+            (depth == 0).then_some(start + index + 1)
+        });
+
+        let Some(end) = end else {
+            break;
+        };
+
+        ranges.push(start..end);
+        from = end;
+    }
+
+    ranges
+}
+
+impl MaskingRules {
+    /// Checks that the custom patterns are valid regexes.
+    pub fn validate(&self) -> Result<(), regex::Error> {
+        for pattern in &self.patterns {
+            Regex::new(pattern)?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the regexes of the enabled rules. Invalid patterns, which the masking dialog does
+    /// not save, are skipped.
+    fn regexes(&self) -> Vec<Regex> {
+        let mut regexes = [
+            (self.format_specifiers, &FORMAT_SPECIFIER),
+            (self.markup, &MARKUP),
+            (self.urls, &URL),
+            (self.emails, &EMAIL),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, regex)| Regex::clone(regex))
+        .collect::<Vec<_>>();
+
+        regexes.extend(
+            self.patterns
+                .iter()
+                .filter_map(|pattern| Regex::new(pattern).ok()),
+        );
+
+        regexes
+    }
+
+    /// Replaces the protected parts of the text with numbered tokens. Where matches overlap, the
+    /// one starting first wins, then the longest.
+    pub fn mask(&self, text: &str) -> Masked {
+        if !self.enabled {
+            return Masked {
+                text: text.to_owned(),
+                tokens: Vec::new(),
+            };
+        }
+
+        let mut ranges = Vec::new();
+
+        if self.icu_messages {
+            ranges.extend(icu_messages(text));
+        }
+
+        for regex in self.regexes() {
+            ranges.extend(regex.find_iter(text).map(|m| m.range()));
+        }
+
+        // Text that already looks like a token is masked too, so it comes back as written.
+        ranges.extend(TOKEN.find_iter(text).map(|m| m.range()));
+
+        ranges.retain(|range| !range.is_empty());
+        ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
+
+        let mut masked = String::with_capacity(text.len());
+        let mut tokens = Vec::new();
+        let mut end = 0;
+
+        for range in ranges {
+            if range.start < end {
+                continue;
+            }
+
+            masked.push_str(&text[end..range.start]);
+            tokens.push(text[range.clone()].to_owned());
+            masked.push_str(&format!("⟦{}⟧", tokens.len()));
+            end = range.end;
+        }
+
+        masked.push_str(&text[end..]);
+
+        Masked {
+            text: masked,
+            tokens,
+        }
+    }
+}
+
+/// A text with its protected parts replaced by tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Masked {
+    pub text: String,
+    /// The protected parts; `⟦1⟧` stands for the first.
+    pub tokens: Vec<String>,
+}
+
+impl Masked {
+    pub fn unmasker(&self) -> Unmasker {
+        Unmasker {
+            tokens: self.tokens.clone(),
+            counts: vec![0; self.tokens.len()],
+            pending: String::new(),
+        }
+    }
+}
+
+/// Restores the tokens in streamed output, counting how often each one appears.
+#[derive(Debug)]
+pub struct Unmasker {
+    tokens: Vec<String>,
+    counts: Vec<usize>,
+    /// The end of the output that may be the start of a token split across chunks.
+    pending: String,
+}
+
+impl Unmasker {
+    /// Returns the chunk with its tokens restored, holding back a partial token until the next
+    /// chunk.
+    pub fn push(&mut self, chunk: &str) -> String {
+        self.pending.push_str(chunk);
+
+        // A token is a few characters long, so a longer unclosed `⟦` is just text.
+        let partial = self.pending.rfind('⟦').filter(|index| {
+            !self.pending[*index..].contains('⟧') && self.pending.len() - index < 16
+        });
+        let split = partial.unwrap_or(self.pending.len());
+
+        let rest = self.pending.split_off(split);
+        let ready = mem::replace(&mut self.pending, rest);
+
+        self.restore(&ready)
+    }
+
+    /// Returns the rest of the output at the end of the stream.
+    pub fn finish(&mut self) -> String {
+        let rest = mem::take(&mut self.pending);
+
+        self.restore(&rest)
+    }
+
+    fn restore(&mut self, text: &str) -> String {
+        let (tokens, counts) = (&self.tokens, &mut self.counts);
+
+        TOKEN
+            .replace_all(text, |captures: &Captures| {
+                match captures[1]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| index.checked_sub(1))
+                    .filter(|index| *index < tokens.len())
+                {
+                    Some(index) => {
+                        counts[index] += 1;
+                        tokens[index].clone()
+                    }
+                    // Tokens the model made up are left as they are.
+                    None => captures[0].to_owned(),
+                }
+            })
+            .into_owned()
+    }
+
+    /// Returns the protected parts that did not come back exactly once.
+    pub fn report(&self) -> MaskReport {
+        let mut report = MaskReport::default();
+
+        for (token, count) in self.tokens.iter().zip(&self.counts) {
+            match count {
+                0 => report.missing.push(token.clone()),
+                1 => {}
+                _ => report.duplicated.push(token.clone()),
+            }
+        }
+
+        report
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MaskReport {
+    pub missing: Vec<String>,
+    pub duplicated: Vec<String>,
+}
+
+impl MaskReport {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.duplicated.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let rules = MaskingRules::default();
+
+        let masked = rules.mask("Hello {name}, you have %d items <b>now</b>");
+        assert_eq!(masked.text, "Hello ⟦1⟧, you have ⟦2⟧ items ⟦3⟧now⟦4⟧");
+        assert_eq!(masked.tokens, ["{name}", "%d", "<b>", "</b>"]);
+
+        let masked = rules.mask(
+            "{count, plural, one {# file} other {# files}} in $1 at https://example.com/a?b=1. Mail a.b@example.com &amp; 50% off",
+        );
+        assert_eq!(masked.text, "⟦1⟧ in ⟦2⟧ at ⟦3⟧. Mail ⟦4⟧ ⟦5⟧ 50% off");
+        assert_eq!(masked.tokens[2], "https://example.com/a?b=1");
+
+        let rules = MaskingRules {
+            markup: false,
+            patterns: vec![r"\bJIRA-\d+\b".to_owned(), "(".to_owned()],
+            ..Default::default()
+        };
+        let masked = rules.mask("Fix JIRA-12 in <b>{0}</b>");
+        assert_eq!(masked.text, "Fix ⟦1⟧ in <b>⟦2⟧</b>");
+        assert!(rules.validate().is_err());
+        assert!(MaskingRules::default().validate().is_ok());
+
+        let rules = MaskingRules {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(rules.mask("Hello {name}").tokens.is_empty());
+    }
+
+    #[test]
+    fn test_unmask() {
+        let masked = MaskingRules::default().mask("Hello {name}, <b>now</b>");
+        let mut unmasker = masked.unmasker();
+
+        let mut output = String::new();
+        for chunk in ["Hallo ⟦", " 1⟧, ⟦2", "⟧jetzt⟦2⟧ ⟦9⟧"] {
+            output.push_str(&unmasker.push(chunk));
+        }
+        output.push_str(&unmasker.finish());
+
+        assert_eq!(output, "Hallo {name}, <b>jetzt<b> ⟦9⟧");
+        assert_eq!(
+            unmasker.report(),
+            MaskReport {
+                missing: vec!["</b>".to_owned()],
+                duplicated: vec!["<b>".to_owned()],
+            }
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::config::Config;
+use crate::masking::MaskingRules;
+use gpui::{div, prelude::*, px, App, Entity, SharedString, Window};
+use gpui_component::checkbox::Checkbox;
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::label::Label;
+use gpui_component::notification::Notification;
+use gpui_component::{ActiveTheme, Disableable, WindowExt};
+
+pub struct MaskingForm {
+    /// The text shown masked in the preview, the current input when there is one.
+    sample: String,
+    rules: MaskingRules,
+    patterns: Entity<InputState>,
+}
+
+impl MaskingForm {
+    pub fn new(
+        sample: String,
+        rules: MaskingRules,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let patterns = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(3)
+                .placeholder(t!("masking.patterns.placeholder"))
+                .default_value(rules.patterns.join("\n"))
+        });
+
+        // Keep the preview up to date.
+        cx.subscribe(&patterns, |_, _, event: &InputEvent, cx| {
+            if matches!(event, InputEvent::Change) {
+                cx.notify();
+            }
+        })
+        .detach();
+
+        MaskingForm {
+            sample,
+            rules,
+            patterns,
+        }
+    }
+
+    pub fn rules(&self, cx: &App) -> Result<MaskingRules, String> {
+        let patterns = self
+            .patterns
+            .read(cx)
+            .value()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        let rules = MaskingRules {
+            patterns,
+            ..self.rules.clone()
+        };
+
+        rules
+            .validate()
+            .map_err(|err| format!("{}: {err}", t!("masking.invalid")))?;
+
+        Ok(rules)
+    }
+
+    fn rule(
+        &self,
+        id: &'static str,
+        label: impl Into<SharedString>,
+        checked: bool,
+        cx: &Context<Self>,
+        set: fn(&mut MaskingRules, bool),
+    ) -> impl IntoElement {
+        Checkbox::new(id)
+            .label(label)
+            .checked(checked)
+            .disabled(!self.rules.enabled)
+            .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                set(&mut this.rules, *checked);
+                cx.notify();
+            }))
+    }
+}
+
+impl Render for MaskingForm {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let rules = &self.rules;
+
+        let preview = match self.rules(cx) {
+            Ok(rules) => Label::new(rules.mask(&self.sample).text)
+                .font_family(cx.theme().mono_font_family.clone()),
+            Err(err) => Label::new(err).text_color(cx.theme().danger),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                Checkbox::new("enabled")
+                    .label(t!("masking.enabled"))
+                    .checked(rules.enabled)
+                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                        this.rules.enabled = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .pl_6()
+                    .child(self.rule(
+                        "format-specifiers",
+                        t!("masking.format-specifiers"),
+                        rules.format_specifiers,
+                        cx,
+                        |rules, checked| rules.format_specifiers = checked,
+                    ))
+                    .child(self.rule(
+                        "icu-messages",
+                        t!("masking.icu-messages"),
+                        rules.icu_messages,
+                        cx,
+                        |rules, checked| rules.icu_messages = checked,
+                    ))
+                    .child(self.rule(
+                        "markup",
+                        t!("masking.markup"),
+                        rules.markup,
+                        cx,
+                        |rules, checked| rules.markup = checked,
+                    ))
+                    .child(self.rule(
+                        "urls",
+                        t!("masking.urls"),
+                        rules.urls,
+                        cx,
+                        |rules, checked| rules.urls = checked,
+                    ))
+                    .child(self.rule(
+                        "emails",
+                        t!("masking.emails"),
+                        rules.emails,
+                        cx,
+                        |rules, checked| rules.emails = checked,
+                    )),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(Label::new(t!("masking.patterns")).text_sm())
+                    .child(Input::new(&self.patterns)),
+            )
+            .child(
+                Label::new(t!("masking.description"))
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(Label::new(t!("masking.preview")).text_sm())
+                    .child(
+                        div()
+                            .id("masking-preview")
+                            .max_h(px(120.))
+                            .overflow_y_scroll()
+                            .p_2()
+                            .rounded_md()
+                            .bg(cx.theme().muted)
+                            .child(preview.text_xs()),
+                    ),
+            )
+    }
+}
+
+pub fn open_masking_dialog(
+    config: Entity<Config>,
+    sample: String,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let rules = config.read(cx).masking().clone();
+    let view = cx.new(|cx| MaskingForm::new(sample, rules, window, cx));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        let view = view.clone();
+        let config = config.clone();
+
+        dialog
+            .confirm()
+            .title(t!("masking").to_string())
+            .child(view.clone())
+            .on_ok(move |_, window, cx| {
+                let rules = match view.read(cx).rules(cx) {
+                    Ok(rules) => rules,
+                    Err(err) => {
+                        window.push_notification(Notification::error(err), cx);
+                        return false;
+                    }
+                };
+
+                config.update(cx, |this, cx| {
+                    this.set_masking(rules, cx);
+                });
+
+                true
+            })
+    });
+}
@@ -1,25 +1,117 @@
-use crate::{About, ChangeModel, Exit, Open, Repository, SaveInput, SaveOutput};
+use crate::model_filter::is_recommended;
+use crate::{
+    About, ChangeModel, ChangePollInterval, ConnectionSettings, EditGlossary, EditMasking,
+    EditModelFilter, EditModelPreset, EditPromptTemplate, EditTranslationStyle, Exit, OllamaHost,
+    Open, PullModel, Refresh, Repository, SaveInput, SaveOutput, ShowModelInfo, ShowOllamaLogs,
+    ToggleFavoritePair, ToggleManageOllama, TogglePreloadModel, ToggleShowAllModels,
+};
 use gpui::{Menu, MenuItem, SharedString};
+use std::time::Duration;
 
+/// The polling intervals offered in the Model menu, in seconds.
+const POLL_INTERVALS: [u64; 4] = [15, 60, 300, 900];
+
+/// Builds the Model menu. Each model comes with the servers it is on, which are shown when
+/// there are several servers.
 pub fn model_menu(
-    models: impl IntoIterator<Item = impl Into<SharedString>>,
+    models: impl IntoIterator<Item = (impl Into<SharedString>, Vec<String>)>,
     selected_model: Option<impl Into<SharedString>>,
+    show_all_models: bool,
+    preload_model: bool,
+    poll_interval: Duration,
+    manage_ollama: bool,
+    endpoints: Vec<(String, String)>,
 ) -> Menu {
     let selected_model = selected_model.map(Into::into);
 
-    Menu {
-        name: t!("model").into(),
-        items: models
+    let item = |(model, locations): (SharedString, Vec<String>)| {
+        let checked = selected_model
+            .as_ref()
+            .map_or(false, |selected| selected == &model);
+
+        let label = if locations.is_empty() {
+            model.clone()
+        } else {
+            format!("{model} ({})", locations.join(", ")).into()
+        };
+
+        MenuItem::action(label, ChangeModel::new(model.to_string())).checked(checked)
+    };
+
+    let (recommended, others): (Vec<_>, Vec<_>) = models
+        .into_iter()
+        .map(|(model, locations)| (model.into(), locations))
+        .partition(|(model, _): &(SharedString, Vec<String>)| is_recommended(model));
+
+    let mut items = recommended.into_iter().map(item).collect::<Vec<_>>();
+
+    if !others.is_empty() {
+        if !items.is_empty() {
+            items.push(MenuItem::Separator);
+        }
+        items.push(MenuItem::submenu(Menu {
+            name: t!("model.others").into(),
+            items: others.into_iter().map(item).collect(),
+        }));
+    }
+
+    if !items.is_empty() {
+        items.push(MenuItem::Separator);
+        items.push(MenuItem::action(t!("model-info.menu"), ShowModelInfo));
+        items.push(MenuItem::action(t!("model-preset.menu"), EditModelPreset));
+    }
+    items.push(MenuItem::action(t!("pull.menu"), PullModel));
+    items.push(MenuItem::Separator);
+    items.push(
+        MenuItem::action(t!("model-filter.show-all"), ToggleShowAllModels).checked(show_all_models),
+    );
+    items.push(MenuItem::action(t!("model-filter.menu"), EditModelFilter));
+    items.push(MenuItem::action(t!("preload.menu"), TogglePreloadModel).checked(preload_model));
+    items.push(MenuItem::Separator);
+    items.push(MenuItem::action(t!("refresh"), Refresh));
+    items.push(MenuItem::submenu(Menu {
+        name: t!("poll-interval").into(),
+        items: POLL_INTERVALS
             .into_iter()
-            .map(|model| {
-                let model = model.into();
-                let checked = selected_model
-                    .as_ref()
-                    .map_or(false, |selected| selected == &model);
+            .map(|secs| {
+                let name = if secs < 60 {
+                    t!("poll-interval.seconds", count = secs)
+                } else {
+                    t!("poll-interval.minutes", count = secs / 60)
+                };
 
-                MenuItem::action(&model, ChangeModel::new(model.to_string())).checked(checked)
+                MenuItem::action(name, ChangePollInterval::new(secs))
+                    .checked(poll_interval.as_secs() == secs)
             })
-            .collect::<Vec<_>>(),
+            .collect(),
+    }));
+    items.push(MenuItem::action(t!("ollama-host.menu"), OllamaHost));
+    if let [(_, base_url)] = endpoints.as_slice() {
+        items.push(MenuItem::action(
+            t!("connection-settings.menu"),
+            ConnectionSettings::new(base_url),
+        ));
+    } else {
+        items.push(MenuItem::submenu(Menu {
+            name: t!("connection-settings").into(),
+            items: endpoints
+                .into_iter()
+                .map(|(label, base_url)| MenuItem::action(label, ConnectionSettings::new(base_url)))
+                .collect(),
+        }));
+    }
+    items
+        .push(MenuItem::action(t!("ollama-serve.menu"), ToggleManageOllama).checked(manage_ollama));
+    if manage_ollama {
+        items.push(MenuItem::action(
+            t!("ollama-serve.logs.menu"),
+            ShowOllamaLogs,
+        ));
+    }
+
+    Menu {
+        name: t!("model").into(),
+        items,
     }
 }
 
@@ -42,6 +134,20 @@ pub fn file_menu() -> Menu {
     }
 }
 
+pub fn translation_menu() -> Menu {
+    Menu {
+        name: t!("translation").into(),
+        items: vec![
+            MenuItem::action(t!("translation-style.menu"), EditTranslationStyle),
+            MenuItem::action(t!("glossary.menu"), EditGlossary),
+            MenuItem::action(t!("masking.menu"), EditMasking),
+            MenuItem::action(t!("prompt-template.menu"), EditPromptTemplate),
+            MenuItem::Separator,
+            MenuItem::action(t!("quick-pairs.menu"), ToggleFavoritePair),
+        ],
+    }
+}
+
 pub fn help_menu() -> Menu {
     Menu {
         name: t!("help").into(),
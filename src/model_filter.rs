@@ -0,0 +1,166 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// The model family the app is built for, listed first in the Model menu.
+pub const RECOMMENDED_MODEL: &str = "translategemma";
+
+/// A pattern matched against model names, e.g. `{ prefix = "translategemma" }` or
+/// `{ regex = "^acme/tg-" }` in the config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelPattern {
+    Prefix(String),
+    Regex(String),
+}
+
+/// Parses one line of the model filter dialog: `/.../` is a regex, anything else a prefix.
+/// Regexes are checked by [`ModelFilter::validate`].
+impl From<&str> for ModelPattern {
+    fn from(s: &str) -> Self {
+        let s = s.trim();
+
+        match s
+            .strip_prefix('/')
+            .and_then(|s| s.strip_suffix('/'))
+            .filter(|s| !s.is_empty())
+        {
+            Some(regex) => ModelPattern::Regex(regex.to_owned()),
+            None => ModelPattern::Prefix(s.to_owned()),
+        }
+    }
+}
+
+impl Display for ModelPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelPattern::Prefix(prefix) => write!(f, "{prefix}"),
+            ModelPattern::Regex(regex) => write!(f, "/{regex}/"),
+        }
+    }
+}
+
+/// Decides which installed models are offered for translation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelFilter {
+    /// Ignores the patterns and lists every installed model.
+    pub show_all: bool,
+    pub patterns: Vec<ModelPattern>,
+}
+
+impl Default for ModelFilter {
+    fn default() -> Self {
+        ModelFilter {
+            show_all: false,
+            patterns: vec![ModelPattern::Prefix(RECOMMENDED_MODEL.to_owned())],
+        }
+    }
+}
+
+impl ModelFilter {
+    /// Checks that the regex patterns are valid.
+    pub fn validate(&self) -> Result<(), regex::Error> {
+        for pattern in &self.patterns {
+            if let ModelPattern::Regex(regex) = pattern {
+                Regex::new(regex)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds a matcher for model names. Invalid regexes, which the model filter dialog does not
+    /// save, are skipped.
+    pub fn matcher(&self) -> impl Fn(&str) -> bool + use<> {
+        let show_all = self.show_all;
+
+        let mut prefixes = Vec::new();
+        let mut regexes = Vec::new();
+
+        for pattern in &self.patterns {
+            match pattern {
+                ModelPattern::Prefix(prefix) => prefixes.push(prefix.clone()),
+                ModelPattern::Regex(regex) => regexes.extend(Regex::new(regex).ok()),
+            }
+        }
+
+        move |name| {
+            show_all
+                || prefixes.iter().any(|prefix| name.starts_with(prefix))
+                || regexes.iter().any(|regex| regex.is_match(name))
+        }
+    }
+}
+
+/// Returns `true` for TranslateGemma models, including ones under a namespace such as
+/// `acme/translategemma:12b`.
+pub fn is_recommended(name: &str) -> bool {
+    name.rsplit('/')
+        .next()
+        .is_some_and(|name| name.starts_with(RECOMMENDED_MODEL))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_pattern() {
+        assert_eq!(
+            ModelPattern::from("translategemma"),
+            ModelPattern::Prefix("translategemma".to_owned())
+        );
+        assert_eq!(
+            ModelPattern::from(" /^acme/tg-/ "),
+            ModelPattern::Regex("^acme/tg-".to_owned())
+        );
+
+        for pattern in ["gemma3", "/^acme/"] {
+            assert_eq!(ModelPattern::from(pattern).to_string(), pattern);
+        }
+    }
+
+    #[test]
+    fn test_model_filter() {
+        let matches = ModelFilter::default().matcher();
+        assert!(matches("translategemma:12b"));
+        assert!(!matches("gemma3:4b"));
+
+        let filter = ModelFilter {
+            show_all: false,
+            patterns: vec![
+                ModelPattern::Prefix("translategemma".to_owned()),
+                ModelPattern::Regex(r"^acme/tg-\w+:".to_owned()),
+            ],
+        };
+        assert!(filter.validate().is_ok());
+        let matches = filter.matcher();
+        assert!(matches("acme/tg-legal:12b"));
+        assert!(!matches("acme/other:12b"));
+
+        let filter = ModelFilter {
+            show_all: false,
+            patterns: vec![ModelPattern::from("/(/"), ModelPattern::from("gemma3")],
+        };
+        assert!(filter.validate().is_err());
+        let matches = filter.matcher();
+        assert!(matches("gemma3:4b"));
+        assert!(!matches("("));
+
+        let matches = ModelFilter {
+            show_all: true,
+            patterns: Vec::new(),
+        }
+        .matcher();
+        assert!(matches("gemma3:4b"));
+    }
+
+    #[test]
+    fn test_is_recommended() {
+        assert!(is_recommended("translategemma"));
+        assert!(is_recommended("acme/translategemma:12b"));
+        assert!(!is_recommended("acme/tg-legal:12b"));
+        assert!(!is_recommended("gemma3"));
+    }
+}
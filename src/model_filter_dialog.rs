@@ -0,0 +1,116 @@
+use crate::config::Config;
+use crate::model_filter::{ModelFilter, ModelPattern};
+use gpui::{div, prelude::*, App, Entity, Window};
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::label::Label;
+use gpui_component::notification::Notification;
+use gpui_component::{ActiveTheme, WindowExt};
+
+pub struct ModelFilterForm {
+    show_all: bool,
+    patterns: Entity<InputState>,
+}
+
+impl ModelFilterForm {
+    pub fn new(filter: &ModelFilter, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let patterns = filter
+            .patterns
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let patterns = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(5)
+                .placeholder(t!("model-filter.placeholder"))
+                .default_value(patterns)
+        });
+
+        // Keep the error up to date.
+        cx.subscribe(&patterns, |_, _, event: &InputEvent, cx| {
+            if matches!(event, InputEvent::Change) {
+                cx.notify();
+            }
+        })
+        .detach();
+
+        ModelFilterForm {
+            show_all: filter.show_all,
+            patterns,
+        }
+    }
+
+    pub fn filter(&self, cx: &App) -> Result<ModelFilter, String> {
+        let patterns = self
+            .patterns
+            .read(cx)
+            .value()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(ModelPattern::from)
+            .collect();
+
+        let filter = ModelFilter {
+            show_all: self.show_all,
+            patterns,
+        };
+
+        filter
+            .validate()
+            .map_err(|err| format!("{}: {err}", t!("model-filter.invalid")))?;
+
+        Ok(filter)
+    }
+}
+
+impl Render for ModelFilterForm {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(Input::new(&self.patterns))
+            .child(
+                Label::new(t!("model-filter.description"))
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .when_some(self.filter(cx).err(), |this, err| {
+                this.child(Label::new(err).text_xs().text_color(cx.theme().danger))
+            })
+    }
+}
+
+pub fn open_model_filter_dialog(config: Entity<Config>, window: &mut Window, cx: &mut App) {
+    let filter = config.read(cx).model_filter().clone();
+    let view = cx.new(|cx| ModelFilterForm::new(&filter, window, cx));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        let view = view.clone();
+        let config = config.clone();
+
+        dialog
+            .confirm()
+            .title(t!("model-filter").to_string())
+            .child(view.clone())
+            .on_ok(move |_, window, cx| {
+                let filter = match view.read(cx).filter(cx) {
+                    Ok(filter) => filter,
+                    Err(err) => {
+                        window.push_notification(Notification::error(err), cx);
+                        return false;
+                    }
+                };
+
+                config.update(cx, |this, cx| {
+                    // Keep a change made from the menu while the dialog was open.
+                    let show_all = this.model_filter().show_all;
+                    this.set_model_filter(ModelFilter { show_all, ..filter }, cx);
+                });
+
+                true
+            })
+    });
+}
@@ -0,0 +1,247 @@
+use crate::config::Config;
+use crate::error::describe_ollama_error;
+use crate::model_pull::format_size;
+use crate::ollama::ShowResponse;
+use crate::ollama_service::OllamaService;
+use gpui::{div, prelude::*, px, AnyElement, App, Context, Entity, SharedString, Task, Window};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::description_list::DescriptionList;
+use gpui_component::label::Label;
+use gpui_component::{gray_600, ActiveTheme, Disableable, Sizable, WindowExt};
+
+enum InfoState {
+    Loading,
+    Loaded(Box<ShowResponse>),
+    Failed(SharedString),
+}
+
+pub struct ModelInfo {
+    ollama_service: Entity<OllamaService>,
+    config: Entity<Config>,
+    model: Option<String>,
+    state: InfoState,
+    task: Option<Task<()>>,
+}
+
+impl ModelInfo {
+    pub fn new(
+        ollama_service: Entity<OllamaService>,
+        config: Entity<Config>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let model = config.read(cx).model().cloned().or_else(|| {
+            ollama_service
+                .read(cx)
+                .models
+                .first()
+                .map(|model| model.name.clone())
+        });
+
+        let mut this = ModelInfo {
+            ollama_service,
+            config,
+            model: None,
+            state: InfoState::Loading,
+            task: None,
+        };
+
+        if let Some(model) = model {
+            this.select(model, cx);
+        }
+
+        this
+    }
+
+    fn select(&mut self, model: String, cx: &mut Context<Self>) {
+        let show = self
+            .ollama_service
+            .update(cx, |this, cx| this.show(model.clone(), cx));
+
+        self.model = Some(model);
+        self.state = InfoState::Loading;
+
+        self.task = Some(cx.spawn(async move |this, cx| {
+            let result = show.await;
+
+            this.update(cx, |this, cx| {
+                this.state = match result {
+                    Ok(info) => InfoState::Loaded(Box::new(info)),
+                    Err(err) => {
+                        let (summary, _) = describe_ollama_error(&err);
+                        InfoState::Failed(format!("{summary}: {err}").into())
+                    }
+                };
+                cx.notify();
+            })
+            .ok();
+        }));
+
+        cx.notify();
+    }
+
+    fn use_model(&mut self, cx: &mut Context<Self>) {
+        if let Some(model) = self.model.clone() {
+            self.config.update(cx, |this, cx| {
+                this.set_model(model, cx);
+            });
+            cx.notify();
+        }
+    }
+
+    fn render_details(&self, info: &ShowResponse, cx: &mut Context<Self>) -> AnyElement {
+        let text = |text: String| Label::new(text).text_sm().into_any_element();
+        let code = |id: &'static str, text: String| {
+            div()
+                .id(id)
+                .max_h(px(120.))
+                .overflow_y_scroll()
+                .child(
+                    Label::new(text)
+                        .text_xs()
+                        .font_family(cx.theme().mono_font_family.clone()),
+                )
+                .into_any_element()
+        };
+
+        let size = self
+            .model
+            .as_deref()
+            .and_then(|model| self.ollama_service.read(cx).model(model))
+            .and_then(|model| model.size)
+            .map(format_size);
+
+        let mut list = DescriptionList::horizontal()
+            .columns(1)
+            .bordered(false)
+            .item(
+                t!("model-info.family").to_string(),
+                text(info.details.family.clone()),
+                1,
+            )
+            .item(
+                t!("model-info.parameter-size").to_string(),
+                text(info.details.parameter_size.clone()),
+                1,
+            )
+            .item(
+                t!("model-info.quantization").to_string(),
+                text(info.details.quantization_level.clone()),
+                1,
+            );
+
+        if let Some(size) = size {
+            list = list.item(t!("model-info.size").to_string(), text(size), 1);
+        }
+
+        if let Some(context_length) = info.context_length() {
+            list = list.item(
+                t!("model-info.context-length").to_string(),
+                text(context_length.to_string()),
+                1,
+            );
+        }
+
+        if !info.capabilities.is_empty() {
+            list = list.item(
+                t!("model-info.capabilities").to_string(),
+                text(info.capabilities.join(", ")),
+                1,
+            );
+        }
+
+        if let Some(parameters) = &info.parameters {
+            list = list.item(
+                t!("model-info.parameters").to_string(),
+                code("parameters", parameters.trim().to_owned()),
+                1,
+            );
+        }
+
+        if let Some(template) = &info.template {
+            list = list.item(
+                t!("model-info.template").to_string(),
+                code("template", template.trim().to_owned()),
+                1,
+            );
+        }
+
+        list.into_any_element()
+    }
+}
+
+impl Render for ModelInfo {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let models = self
+            .ollama_service
+            .read(cx)
+            .models
+            .iter()
+            .map(|model| model.name.clone())
+            .collect::<Vec<_>>();
+
+        let in_use = self.model.is_some() && self.model.as_ref() == self.config.read(cx).model();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_wrap()
+                    .gap_2()
+                    .children(models.into_iter().map(|model| {
+                        let selected = self.model.as_ref() == Some(&model);
+
+                        Button::new(SharedString::from(model.clone()))
+                            .small()
+                            .label(model.clone())
+                            .when(selected, |this| this.primary())
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.select(model.clone(), cx);
+                            }))
+                    })),
+            )
+            .child(match &self.state {
+                _ if self.model.is_none() => Label::new(t!("model-info.no-model"))
+                    .text_sm()
+                    .text_color(gray_600())
+                    .into_any_element(),
+                InfoState::Loading => Label::new(t!("model-info.loading"))
+                    .text_sm()
+                    .text_color(gray_600())
+                    .into_any_element(),
+                InfoState::Loaded(info) => self.render_details(info, cx),
+                InfoState::Failed(message) => Label::new(message.clone())
+                    .text_sm()
+                    .text_color(cx.theme().red)
+                    .into_any_element(),
+            })
+            .child(
+                div().flex().flex_row().justify_end().child(
+                    Button::new("use-model")
+                        .small()
+                        .primary()
+                        .label(t!("model-info.use"))
+                        .disabled(self.model.is_none() || in_use)
+                        .on_click(cx.listener(|this, _, _, cx| this.use_model(cx))),
+                ),
+            )
+    }
+}
+
+pub fn open_model_info_dialog(
+    ollama_service: Entity<OllamaService>,
+    config: Entity<Config>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let view = cx.new(|cx| ModelInfo::new(ollama_service, config, cx));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(t!("model-info").to_string())
+            .child(view.clone())
+    });
+}
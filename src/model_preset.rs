@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::skip_serializing_none;
+use std::collections::HashMap;
+
+/// Generation options for one model, e.g. `[presets."translategemma:12b"]` in the config file.
+/// Options left unset use the server's defaults.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelPreset {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u32>,
+    /// Makes the output reproducible, together with a temperature of 0.
+    pub seed: Option<i64>,
+    /// The context size in tokens, raised for long inputs.
+    pub num_ctx: Option<u64>,
+    /// The maximum number of tokens to generate, or -1 for no limit.
+    pub num_predict: Option<i64>,
+    pub repeat_penalty: Option<f64>,
+    /// How long Ollama keeps the model loaded after a translation, e.g. `10m` or `-1`.
+    pub keep_alive: Option<String>,
+}
+
+impl ModelPreset {
+    pub fn is_empty(&self) -> bool {
+        self == &ModelPreset::default()
+    }
+
+    /// Checks that the options are in range, returning the name of the first one that is not.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let checks = [
+            (
+                "temperature",
+                self.temperature.is_none_or(|value| value >= 0.0),
+            ),
+            (
+                "top_p",
+                self.top_p.is_none_or(|value| (0.0..=1.0).contains(&value)),
+            ),
+            ("num_ctx", self.num_ctx != Some(0)),
+            (
+                "num_predict",
+                self.num_predict.is_none_or(|value| value >= -1),
+            ),
+            (
+                "repeat_penalty",
+                self.repeat_penalty.is_none_or(|value| value >= 0.0),
+            ),
+        ];
+
+        match checks.into_iter().find(|(_, valid)| !valid) {
+            Some((name, _)) => Err(name),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the options in the form of Ollama's `options` field, or `None` if none are set.
+    /// `keep_alive` is a separate field of the request.
+    pub fn ollama_options(&self) -> Option<HashMap<String, Value>> {
+        let options = [
+            ("temperature", self.temperature.map(Value::from)),
+            ("top_p", self.top_p.map(Value::from)),
+            ("top_k", self.top_k.map(Value::from)),
+            ("seed", self.seed.map(Value::from)),
+            ("num_ctx", self.num_ctx.map(Value::from)),
+            ("num_predict", self.num_predict.map(Value::from)),
+            ("repeat_penalty", self.repeat_penalty.map(Value::from)),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.to_owned(), value?)))
+        .collect::<HashMap<_, _>>();
+
+        (!options.is_empty()).then_some(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_model_preset() {
+        let preset = toml::from_str::<ModelPreset>("temperature = 0.0\nseed = 42").unwrap();
+
+        assert_eq!(
+            preset,
+            ModelPreset {
+                temperature: Some(0.0),
+                seed: Some(42),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            toml::to_string(&preset).unwrap(),
+            "temperature = 0.0\nseed = 42\n"
+        );
+
+        assert_eq!(
+            serde_json::to_value(preset.ollama_options()).unwrap(),
+            json!({ "temperature": 0.0, "seed": 42 })
+        );
+        assert!(ModelPreset::default().ollama_options().is_none());
+        assert!(ModelPreset::default().is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(ModelPreset::default().validate(), Ok(()));
+
+        let top_p = ModelPreset {
+            top_p: Some(1.5),
+            ..Default::default()
+        };
+        assert_eq!(top_p.validate(), Err("top_p"));
+
+        let num_ctx = ModelPreset {
+            num_ctx: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(num_ctx.validate(), Err("num_ctx"));
+
+        let unlimited = ModelPreset {
+            num_predict: Some(-1),
+            ..Default::default()
+        };
+        assert_eq!(unlimited.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::config::Config;
+use crate::model_preset::ModelPreset;
+use gpui::{div, prelude::*, App, Entity, SharedString, Window};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Input, InputState};
+use gpui_component::label::Label;
+use gpui_component::notification::Notification;
+use gpui_component::{ActiveTheme, Sizable, WindowExt};
+use std::str::FromStr;
+
+/// The seed filled in by the Reproducible button when none is set.
+const DEFAULT_SEED: i64 = 42;
+
+/// Parses an optional number, naming the option in the error.
+fn parse<T: FromStr>(name: &str, text: &str) -> Result<Option<T>, String> {
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    text.parse()
+        .map(Some)
+        .map_err(|_| format!("{}: {name}: {text:?}", t!("model-preset.invalid")))
+}
+
+fn to_text<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+pub struct ModelPresetForm {
+    temperature: Entity<InputState>,
+    top_p: Entity<InputState>,
+    top_k: Entity<InputState>,
+    seed: Entity<InputState>,
+    num_ctx: Entity<InputState>,
+    num_predict: Entity<InputState>,
+    repeat_penalty: Entity<InputState>,
+    keep_alive: Entity<InputState>,
+}
+
+impl ModelPresetForm {
+    pub fn new(preset: ModelPreset, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut input = |placeholder: &str, value: String| {
+            cx.new(|cx| {
+                InputState::new(window, cx)
+                    .placeholder(placeholder.to_string())
+                    .default_value(value)
+            })
+        };
+
+        // The placeholders are Ollama's defaults.
+        ModelPresetForm {
+            temperature: input("0.8", to_text(preset.temperature)),
+            top_p: input("0.9", to_text(preset.top_p)),
+            top_k: input("40", to_text(preset.top_k)),
+            seed: input("", to_text(preset.seed)),
+            num_ctx: input("4096", to_text(preset.num_ctx)),
+            num_predict: input("-1", to_text(preset.num_predict)),
+            repeat_penalty: input("1.1", to_text(preset.repeat_penalty)),
+            keep_alive: input("5m", preset.keep_alive.unwrap_or_default()),
+        }
+    }
+
+    /// Returns the preset as entered, or a message describing the first invalid option.
+    pub fn preset(&self, cx: &App) -> Result<ModelPreset, String> {
+        let value = |input: &Entity<InputState>| input.read(cx).value().to_string();
+
+        let preset = ModelPreset {
+            temperature: parse("temperature", &value(&self.temperature))?,
+            top_p: parse("top_p", &value(&self.top_p))?,
+            top_k: parse("top_k", &value(&self.top_k))?,
+            seed: parse("seed", &value(&self.seed))?,
+            num_ctx: parse("num_ctx", &value(&self.num_ctx))?,
+            num_predict: parse("num_predict", &value(&self.num_predict))?,
+            repeat_penalty: parse("repeat_penalty", &value(&self.repeat_penalty))?,
+            keep_alive: Some(value(&self.keep_alive).trim().to_owned())
+                .filter(|keep_alive| !keep_alive.is_empty()),
+        };
+
+        preset
+            .validate()
+            .map_err(|name| format!("{}: {name}", t!("model-preset.out-of-range")))?;
+
+        Ok(preset)
+    }
+
+    /// Sets a temperature of 0 and a fixed seed, so that the same input is always translated
+    /// the same way.
+    fn make_reproducible(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.temperature.update(cx, |this, cx| {
+            this.set_value("0", window, cx);
+        });
+
+        if self.seed.read(cx).value().trim().is_empty() {
+            self.seed.update(cx, |this, cx| {
+                this.set_value(DEFAULT_SEED.to_string(), window, cx);
+            });
+        }
+    }
+}
+
+fn field(label: impl Into<SharedString>, input: &Entity<InputState>) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(Label::new(label).text_sm())
+        .child(Input::new(input))
+}
+
+impl Render for ModelPresetForm {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .grid()
+                    .grid_cols(2)
+                    .gap_3()
+                    .child(field(t!("model-preset.temperature"), &self.temperature))
+                    .child(field(t!("model-preset.seed"), &self.seed))
+                    .child(field(t!("model-preset.top-p"), &self.top_p))
+                    .child(field(t!("model-preset.top-k"), &self.top_k))
+                    .child(field(t!("model-preset.num-ctx"), &self.num_ctx))
+                    .child(field(t!("model-preset.num-predict"), &self.num_predict))
+                    .child(field(
+                        t!("model-preset.repeat-penalty"),
+                        &self.repeat_penalty,
+                    ))
+                    .child(field(t!("model-preset.keep-alive"), &self.keep_alive)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        Button::new("reproducible")
+                            .small()
+                            .outline()
+                            .label(t!("model-preset.reproducible"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.make_reproducible(window, cx);
+                            })),
+                    )
+                    .child(
+                        Label::new(t!("model-preset.description"))
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+    }
+}
+
+pub fn open_model_preset_dialog(
+    config: Entity<Config>,
+    model: String,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let preset = config.read(cx).preset(&model);
+    let view = cx.new(|cx| ModelPresetForm::new(preset, window, cx));
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let view = view.clone();
+        let config = config.clone();
+        let model = model.clone();
+
+        dialog
+            .confirm()
+            .title(t!("model-preset").to_string())
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        Label::new(model.clone())
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(view.clone()),
+            )
+            .on_ok(move |_, window, cx| {
+                let preset = match view.read(cx).preset(cx) {
+                    Ok(preset) => preset,
+                    Err(err) => {
+                        window.push_notification(Notification::error(err), cx);
+                        return false;
+                    }
+                };
+
+                config.update(cx, |this, cx| {
+                    this.set_preset(model.clone(), preset, cx);
+                });
+
+                true
+            })
+    });
+}
@@ -0,0 +1,267 @@
+use crate::error::describe_ollama_error;
+use crate::ollama::{self, PullRequest, PullResponse};
+use crate::ollama_service::OllamaService;
+use futures_util::StreamExt;
+use gpui::{div, prelude::*, AnyElement, App, Context, Entity, SharedString, Task, Window};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::label::Label;
+use gpui_component::progress::Progress;
+use gpui_component::{gray_600, green_600, ActiveTheme, Disableable, Sizable, WindowExt};
+
+/// The TranslateGemma variants offered for pulling, with their parameter sizes.
+const MODELS: &[(&str, &str)] = &[
+    ("translategemma", "4B"),
+    ("translategemma:12b", "12B"),
+    ("translategemma:27b", "27B"),
+];
+
+enum PullState {
+    Idle,
+    Pulling {
+        status: SharedString,
+        completed: u64,
+        total: u64,
+    },
+    Done,
+    Failed(SharedString),
+}
+
+pub struct ModelPull {
+    ollama_service: Entity<OllamaService>,
+    model: &'static str,
+    state: PullState,
+    /// Dropping the task cancels the pull.
+    task: Option<Task<()>>,
+}
+
+impl ModelPull {
+    pub fn new(ollama_service: Entity<OllamaService>) -> Self {
+        ModelPull {
+            ollama_service,
+            model: MODELS[0].0,
+            state: PullState::Idle,
+            task: None,
+        }
+    }
+
+    fn pull(&mut self, cx: &mut Context<Self>) {
+        let Some(client) = self.ollama_service.read(cx).ollama_client() else {
+            self.fail(&ollama::Error::Unsupported("pulling models"), cx);
+            return;
+        };
+        let model = self.model;
+
+        self.state = PullState::Pulling {
+            status: t!("pull.starting").into(),
+            completed: 0,
+            total: 0,
+        };
+        cx.notify();
+
+        self.task = Some(cx.spawn(async move |this, cx| {
+            let mut stream = match client.pull(PullRequest::new(model)).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    this.update(cx, |this, cx| this.fail(&err, cx)).ok();
+                    return;
+                }
+            };
+
+            while let Some(item) = stream.next().await {
+                let result = this.update(cx, |this, cx| match item {
+                    Ok(resp) => this.progress(resp, cx),
+                    Err(err) => this.fail(&err, cx),
+                });
+
+                if result.is_err() {
+                    return;
+                }
+            }
+
+            this.update(cx, |this, cx| this.finish(cx)).ok();
+        }));
+    }
+
+    fn cancel(&mut self, cx: &mut Context<Self>) {
+        self.task = None;
+        self.state = PullState::Idle;
+        cx.notify();
+    }
+
+    fn progress(&mut self, resp: PullResponse, cx: &mut Context<Self>) {
+        if matches!(self.state, PullState::Pulling { .. }) {
+            self.state = PullState::Pulling {
+                status: resp.status.into(),
+                completed: resp.completed.unwrap_or_default(),
+                total: resp.total.unwrap_or_default(),
+            };
+            cx.notify();
+        }
+    }
+
+    fn fail(&mut self, error: &ollama::Error, cx: &mut Context<Self>) {
+        let (summary, _) = describe_ollama_error(error);
+
+        self.state = PullState::Failed(format!("{summary}: {error}").into());
+        cx.notify();
+    }
+
+    fn finish(&mut self, cx: &mut Context<Self>) {
+        if matches!(self.state, PullState::Pulling { .. }) {
+            self.state = PullState::Done;
+
+            self.ollama_service.update(cx, |this, cx| {
+                this.refresh(cx);
+            });
+
+            cx.notify();
+        }
+    }
+
+    fn render_status(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        match &self.state {
+            PullState::Idle => None,
+            PullState::Pulling {
+                status,
+                completed,
+                total,
+            } => {
+                let percentage = if *total > 0 {
+                    *completed as f32 / *total as f32 * 100.
+                } else {
+                    0.
+                };
+
+                Some(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .child(Progress::new().value(percentage))
+                        .child(
+                            div()
+                                .flex()
+                                .flex_row()
+                                .child(Label::new(status.clone()).text_xs().text_color(gray_600()))
+                                .when(*total > 0, |this| {
+                                    this.child(
+                                        Label::new(format!(
+                                            "{} / {}",
+                                            format_size(*completed),
+                                            format_size(*total)
+                                        ))
+                                        .ml_auto()
+                                        .text_xs()
+                                        .text_color(gray_600()),
+                                    )
+                                }),
+                        )
+                        .into_any_element(),
+                )
+            }
+            PullState::Done => Some(
+                Label::new(t!("pull.done", model = self.model))
+                    .text_sm()
+                    .text_color(green_600())
+                    .into_any_element(),
+            ),
+            PullState::Failed(message) => Some(
+                Label::new(message.clone())
+                    .text_sm()
+                    .text_color(cx.theme().red)
+                    .into_any_element(),
+            ),
+        }
+    }
+}
+
+impl Render for ModelPull {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let pulling = matches!(self.state, PullState::Pulling { .. });
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                Label::new(t!("pull.description"))
+                    .text_sm()
+                    .text_color(gray_600()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .children(MODELS.iter().map(|&(model, size)| {
+                        Button::new(model)
+                            .small()
+                            .label(format!("{model} ({size})"))
+                            .when(model == self.model, |this| this.primary())
+                            .disabled(pulling)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.model = model;
+                                cx.notify();
+                            }))
+                    })),
+            )
+            .children(self.render_status(cx))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .justify_end()
+                    .when(pulling, |this| {
+                        this.child(
+                            Button::new("cancel-pull")
+                                .small()
+                                .label(t!("cancel"))
+                                .on_click(cx.listener(|this, _, _, cx| this.cancel(cx))),
+                        )
+                    })
+                    .when(!pulling, |this| {
+                        this.child(
+                            Button::new("pull")
+                                .small()
+                                .primary()
+                                .label(t!("pull"))
+                                .on_click(cx.listener(|this, _, _, cx| this.pull(cx))),
+                        )
+                    }),
+            )
+    }
+}
+
+/// Formats a byte count with decimal units, as Ollama does.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1000. && unit < UNITS.len() - 1 {
+        size /= 1000.;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Opens the pull dialog. Closing the dialog cancels a pull in progress.
+pub fn open_model_pull_dialog(
+    ollama_service: Entity<OllamaService>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let view = cx.new(|_| ModelPull::new(ollama_service));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(t!("pull.title").to_string())
+            .child(view.clone())
+    });
+}
@@ -0,0 +1,147 @@
+use crate::ollama::{ChatRequest, Format, GenerateRequest, Reasoning};
+use semver::Version;
+
+/// A server feature the app relies on that older Ollama builds do not have.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Feature {
+    /// Gemma 3 models, which TranslateGemma is built on.
+    Gemma3,
+    /// JSON schemas in the `format` field.
+    StructuredOutputs,
+    /// The `think` field.
+    Reasoning,
+    /// Effort levels in the `think` field.
+    ReasoningEffort,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 4] = [
+        Feature::Gemma3,
+        Feature::StructuredOutputs,
+        Feature::Reasoning,
+        Feature::ReasoningEffort,
+    ];
+
+    /// The first Ollama release that supports the feature.
+    pub fn min_version(self) -> Version {
+        match self {
+            Feature::Gemma3 => Version::new(0, 6, 0),
+            Feature::StructuredOutputs => Version::new(0, 5, 0),
+            Feature::Reasoning => Version::new(0, 9, 0),
+            Feature::ReasoningEffort => Version::new(0, 11, 0),
+        }
+    }
+
+    pub fn is_supported(self, version: &Version) -> bool {
+        // Builds from source report 0.0.0, so assume they are up to date.
+        if *version == Version::new(0, 0, 0) {
+            return true;
+        }
+
+        // Treat release candidates as the release they precede.
+        Version::new(version.major, version.minor, version.patch) >= self.min_version()
+    }
+}
+
+/// Returns the features the server does not support.
+pub fn unsupported_features(version: &Version) -> Vec<Feature> {
+    Feature::ALL
+        .into_iter()
+        .filter(|feature| !feature.is_supported(version))
+        .collect()
+}
+
+/// Returns the version that supports every feature.
+pub fn recommended_version() -> Version {
+    Feature::ALL
+        .into_iter()
+        .map(Feature::min_version)
+        .max()
+        .unwrap()
+}
+
+/// Replaces a JSON schema with plain JSON mode when schemas are not supported.
+fn degrade_format(format: &mut Option<Format>, version: &Version) {
+    if matches!(format, Some(Format::JSONSchema(_)))
+        && !Feature::StructuredOutputs.is_supported(version)
+    {
+        *format = Some(Format::Json);
+    }
+}
+
+/// Turns an effort level into a plain toggle, or drops the field, when it is not supported.
+fn degrade_reasoning(reasoning: &mut Option<Reasoning>, version: &Version) {
+    if reasoning.is_some() && !Feature::Reasoning.is_supported(version) {
+        *reasoning = None;
+    } else if matches!(reasoning, Some(Reasoning::ReasoningEffort(_)))
+        && !Feature::ReasoningEffort.is_supported(version)
+    {
+        *reasoning = Some(Reasoning::Reasoning(true));
+    }
+}
+
+impl GenerateRequest {
+    /// Adjusts the request so that an older server does not reject it.
+    pub fn degrade(&mut self, version: &Version) {
+        degrade_format(&mut self.format, version);
+        degrade_reasoning(&mut self.reasoning, version);
+    }
+}
+
+impl ChatRequest {
+    /// Adjusts the request so that an older server does not reject it.
+    pub fn degrade(&mut self, version: &Version) {
+        degrade_format(&mut self.format, version);
+        degrade_reasoning(&mut self.reasoning, version);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_supported() {
+        let version = |version: &str| Version::parse(version).unwrap();
+
+        assert!(Feature::Reasoning.is_supported(&version("0.9.0")));
+        assert!(Feature::Reasoning.is_supported(&version("0.9.0-rc1")));
+        assert!(Feature::Reasoning.is_supported(&version("0.0.0")));
+        assert!(!Feature::Reasoning.is_supported(&version("0.8.9")));
+
+        assert_eq!(
+            unsupported_features(&version("0.6.5")),
+            [Feature::Reasoning, Feature::ReasoningEffort]
+        );
+        assert!(unsupported_features(&recommended_version()).is_empty());
+    }
+
+    #[test]
+    fn test_degrade() {
+        let request = || {
+            GenerateRequest::builder()
+                .model("translategemma")
+                .json_schema(json!({ "type": "object" }))
+                .reasoning_low()
+                .build()
+        };
+        let degraded = |version: &str| {
+            let mut request = request();
+            request.degrade(&Version::parse(version).unwrap());
+            serde_json::to_value(&request).unwrap()
+        };
+
+        let current = degraded("0.12.0");
+        assert_eq!(current["format"], json!({ "type": "object" }));
+        assert_eq!(current["think"], json!("low"));
+
+        let old = degraded("0.9.0");
+        assert_eq!(old["format"], json!({ "type": "object" }));
+        assert_eq!(old["think"], json!(true));
+
+        let older = degraded("0.4.0");
+        assert_eq!(older["format"], json!("json"));
+        assert!(older.get("think").is_none());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::ollama::Error;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Proxy};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How to reach an Ollama server, e.g. one behind a reverse proxy with authentication.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionOptions {
+    /// Headers sent with every request, e.g. `Authorization`. They usually hold secrets, so
+    /// they are stored separately from the rest of the config.
+    #[serde(skip)]
+    pub headers: BTreeMap<String, String>,
+    /// A PEM file with root certificates to trust in addition to the system ones.
+    pub ca_bundle: Option<PathBuf>,
+    /// Skips certificate verification. Only meant for development servers.
+    pub accept_invalid_certs: bool,
+    /// An HTTP(S) proxy for all requests, e.g. `http://proxy.internal:3128`.
+    pub proxy: Option<String>,
+    /// How long to wait for a connection, in seconds.
+    pub connect_timeout: Option<u64>,
+    /// How long to wait for each read, in seconds. Unlike a total timeout, this does not cut
+    /// off long streams.
+    pub read_timeout: Option<u64>,
+}
+
+impl ConnectionOptions {
+    pub(crate) fn build_client(&self) -> Result<reqwest::Client, Error> {
+        let mut builder = reqwest::Client::builder();
+
+        if !self.headers.is_empty() {
+            let mut headers = HeaderMap::new();
+
+            for (name, value) in &self.headers {
+                let name = HeaderName::try_from(name.as_str())
+                    .map_err(|err| Error::Options(format!("header {name:?}: {err}")))?;
+                let mut value = HeaderValue::try_from(value.as_str())
+                    .map_err(|err| Error::Options(format!("header {name}: {err}")))?;
+                value.set_sensitive(true);
+
+                headers.insert(name, value);
+            }
+
+            builder = builder.default_headers(headers);
+        }
+
+        if let Some(path) = &self.ca_bundle {
+            let pem = fs::read(path)
+                .map_err(|err| Error::Options(format!("{}: {err}", path.display())))?;
+            let certs = Certificate::from_pem_bundle(&pem)
+                .map_err(|err| Error::Options(format!("{}: {err}", path.display())))?;
+
+            builder = builder.tls_certs_merge(certs);
+        }
+
+        if self.accept_invalid_certs {
+            builder = builder.tls_danger_accept_invalid_certs(true);
+        }
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy)
+                .map_err(|err| Error::Options(format!("proxy {proxy:?}: {err}")))?;
+
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(secs) = self.connect_timeout {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+
+        if let Some(secs) = self.read_timeout {
+            builder = builder.read_timeout(Duration::from_secs(secs));
+        }
+
+        builder
+            .build()
+            .map_err(|err| Error::Options(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_client() {
+        assert!(ConnectionOptions::default().build_client().is_ok());
+
+        let options = ConnectionOptions {
+            headers: BTreeMap::from([("Authorization".to_owned(), "Bearer token".to_owned())]),
+            accept_invalid_certs: true,
+            proxy: Some("http://127.0.0.1:3128".to_owned()),
+            connect_timeout: Some(5),
+            read_timeout: Some(60),
+            ..Default::default()
+        };
+        assert!(options.build_client().is_ok());
+
+        let invalid = [
+            ConnectionOptions {
+                headers: BTreeMap::from([("Bad Header".to_owned(), "value".to_owned())]),
+                ..Default::default()
+            },
+            ConnectionOptions {
+                headers: BTreeMap::from([("Authorization".to_owned(), "line\nbreak".to_owned())]),
+                ..Default::default()
+            },
+            ConnectionOptions {
+                ca_bundle: Some(PathBuf::from("/nonexistent/ca.pem")),
+                ..Default::default()
+            },
+            ConnectionOptions {
+                proxy: Some("not a url".to_owned()),
+                ..Default::default()
+            },
+        ];
+
+        for options in invalid {
+            assert!(
+                matches!(options.build_client(), Err(Error::Options(_))),
+                "{options:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_headers_are_not_serialized() {
+        let options = ConnectionOptions {
+            headers: BTreeMap::from([("Authorization".to_owned(), "Bearer token".to_owned())]),
+            read_timeout: Some(60),
+            ..Default::default()
+        };
+
+        let toml = toml::to_string(&options).unwrap();
+        assert!(!toml.contains("Bearer"));
+        assert_eq!(
+            toml::from_str::<ConnectionOptions>(&toml).unwrap(),
+            ConnectionOptions {
+                read_timeout: Some(60),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+use tokio::task::JoinError;
+use tokio_util::codec::LinesCodecError;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The server could not be reached, usually because Ollama is not running.
+    Connect(reqwest::Error),
+    /// The server did not respond in time.
+    Timeout(reqwest::Error),
+    /// The server responded with a non-success status.
+    Status { status: StatusCode, message: String },
+    /// The requested model has not been pulled.
+    ModelNotFound(String),
+    /// The server reported an error in the middle of a stream.
+    Stream(String),
+    /// A response body or stream line could not be decoded.
+    Decode(Box<dyn std::error::Error + Send + Sync>),
+    /// The server reported a version that is not valid semver.
+    Version(semver::Error),
+    /// Any other transport error.
+    Request(reqwest::Error),
+    /// The task running the request panicked.
+    Task(JoinError),
+    /// The connection options are invalid, e.g. a header value or CA bundle.
+    Options(String),
+    /// The server does not offer the operation, e.g. pulling models from an OpenAI-compatible
+    /// server.
+    Unsupported(&'static str),
+}
+
+/// The body of an Ollama error response, e.g. `{"error": "model 'foo' not found"}`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorResponse {
+    pub error: String,
+}
+
+impl Error {
+    /// Builds an error from a non-success response, using the `error` field of the body when it
+    /// is present. A 404 only means a missing model when the body says so, as a wrong base path,
+    /// e.g. behind a reverse proxy, is a 404 too.
+    pub(crate) fn from_status(status: StatusCode, body: &str, model: Option<&str>) -> Error {
+        let message = match serde_json::from_str::<ErrorResponse>(body) {
+            Ok(ErrorResponse { error }) => error,
+            Err(_) => body.trim().to_owned(),
+        };
+
+        // Ollama answers e.g. `model 'foo' not found` or `model "foo" not found, try pulling it
+        // first`.
+        if status == StatusCode::NOT_FOUND
+            && let Some(model) = model
+            && message.starts_with("model ")
+            && message.contains("not found")
+        {
+            return Error::ModelNotFound(model.to_owned());
+        }
+
+        Error::Status { status, message }
+    }
+
+    /// Returns `true` if the server could not be reached at all.
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, Error::Connect(_) | Error::Timeout(_))
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Connect(err) => write!(f, "failed to connect to the server: {err}"),
+            Error::Timeout(_) => write!(f, "request to the server timed out"),
+            Error::Status { status, message } if message.is_empty() => write!(f, "{status}"),
+            Error::Status { status, message } => write!(f, "{status}: {message}"),
+            Error::ModelNotFound(model) => write!(f, "model '{model}' not found"),
+            Error::Stream(message) => write!(f, "{message}"),
+            Error::Decode(err) => write!(f, "failed to decode response: {err}"),
+            Error::Version(err) => write!(f, "invalid version: {err}"),
+            Error::Request(err) => write!(f, "{err}"),
+            Error::Task(err) => write!(f, "{err}"),
+            Error::Options(message) => write!(f, "invalid connection settings: {message}"),
+            Error::Unsupported(operation) => {
+                write!(f, "{operation} is not supported by this server")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Connect(err) | Error::Timeout(err) | Error::Request(err) => Some(err),
+            Error::Decode(err) => Some(err.as_ref()),
+            Error::Version(err) => Some(err),
+            Error::Task(err) => Some(err),
+            Error::Status { .. }
+            | Error::ModelNotFound(_)
+            | Error::Stream(_)
+            | Error::Options(_)
+            | Error::Unsupported(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_connect() {
+            Error::Connect(err)
+        } else if err.is_timeout() {
+            Error::Timeout(err)
+        } else if err.is_decode() {
+            Error::Decode(Box::new(err))
+        } else {
+            Error::Request(err)
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Decode(Box::new(err))
+    }
+}
+
+impl From<LinesCodecError> for Error {
+    fn from(err: LinesCodecError) -> Self {
+        match err {
+            // Transport errors are wrapped in `io::Error` by the stream reader.
+            LinesCodecError::Io(err) => match err.downcast::<reqwest::Error>() {
+                Ok(err) => err.into(),
+                Err(err) => Error::Decode(Box::new(err)),
+            },
+            err => Error::Decode(Box::new(err)),
+        }
+    }
+}
+
+impl From<semver::Error> for Error {
+    fn from(err: semver::Error) -> Self {
+        Error::Version(err)
+    }
+}
+
+impl From<JoinError> for Error {
+    fn from(err: JoinError) -> Self {
+        Error::Task(err)
+    }
+}
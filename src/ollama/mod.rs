@@ -1,4 +1,13 @@
+mod compat;
+mod connection;
+mod error;
+#[allow(clippy::module_inception)]
 mod ollama;
+mod serve;
 mod types;
 
+pub use compat::*;
+pub use connection::*;
+pub use error::*;
 pub use ollama::*;
+pub use serve::*;
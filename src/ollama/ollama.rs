@@ -1,174 +1,727 @@
+use crate::http::HttpClient;
+use crate::ollama::connection::ConnectionOptions;
+use crate::ollama::error::{Error, ErrorResponse};
 pub use crate::ollama::types::*;
-use anyhow::anyhow;
 use futures_util::stream::BoxStream;
 use futures_util::{stream, StreamExt, TryStreamExt};
 use reqwest::header::ACCEPT;
-use reqwest::RequestBuilder;
+use reqwest::{RequestBuilder, Response};
 use semver::Version;
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::env;
 use std::io;
-use std::sync::LazyLock;
-use tokio::runtime::{Handle, Runtime};
+use tokio::sync::mpsc;
 use tokio_util::codec::{FramedRead, LinesCodec};
 use tokio_util::io::StreamReader;
 use url::Url;
 
-pub const DEFAULT_BASE_URL: &'static str = "http://127.0.0.1:11434";
+pub const DEFAULT_BASE_URL: &str = "http://127.0.0.1:11434";
 
-static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
-    tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .expect("failed to create tokio runtime")
-});
+const DEFAULT_PORT: u16 = 11434;
 
+/// Parses an Ollama host the way `OLLAMA_HOST` is interpreted: the scheme defaults to `http`
+/// and, when no scheme is given, the port defaults to `11434`.
+pub fn parse_host(host: &str) -> Result<Url, url::ParseError> {
+    let host = host.trim();
+
+    let mut url = if host.contains("://") {
+        Url::parse(host)?
+    } else {
+        let mut url = Url::parse(&format!("http://{host}"))?;
+
+        let authority = host.split('/').next().unwrap_or_default();
+        let has_port = authority
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+
+        if !has_port {
+            url.set_port(Some(DEFAULT_PORT))
+                .map_err(|_| url::ParseError::InvalidPort)?;
+        }
+
+        url
+    };
+
+    // `OLLAMA_HOST=0.0.0.0` makes the server listen on all interfaces, it is not an address to connect to.
+    if url.host_str() == Some("0.0.0.0") {
+        url.set_host(Some("127.0.0.1"))?;
+    }
+
+    Ok(url)
+}
+
+/// Returns the base URL from `OLLAMA_HOST`, falling back to [`DEFAULT_BASE_URL`].
+pub fn default_base_url() -> Url {
+    env::var("OLLAMA_HOST")
+        .ok()
+        .filter(|host| !host.trim().is_empty())
+        .and_then(|host| parse_host(&host).ok())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.parse().unwrap())
+}
+
+#[derive(Clone)]
 pub struct Client {
-    base_url: Url,
-    client: reqwest::Client,
-    handle: Handle,
+    http: HttpClient,
+    /// The version of the server, used to leave out fields it does not support.
+    server_version: Option<Version>,
 }
 
 impl Default for Client {
     fn default() -> Self {
-        Client {
-            base_url: DEFAULT_BASE_URL.parse().unwrap(),
-            client: reqwest::Client::new(),
-            handle: RUNTIME.handle().clone(),
-        }
+        Client::new(default_base_url())
     }
 }
 
 impl Client {
-    fn get(&self, path: &'static str) -> RequestBuilder {
-        self.client.get(self.base_url.join(path).unwrap().as_str())
+    pub fn new(base_url: Url) -> Self {
+        Client::with_client(base_url, reqwest::Client::new())
+    }
+
+    /// Creates a client with custom headers, certificates, proxy or timeouts.
+    pub fn with_options(base_url: Url, options: &ConnectionOptions) -> Result<Self, Error> {
+        Ok(Client::with_client(base_url, options.build_client()?))
+    }
+
+    fn with_client(base_url: Url, client: reqwest::Client) -> Self {
+        Client {
+            http: HttpClient::new(base_url, client),
+            server_version: None,
+        }
+    }
+
+    pub fn base_url(&self) -> &Url {
+        self.http.base_url()
     }
 
-    fn post(&self, path: &'static str) -> RequestBuilder {
-        self.client.post(self.base_url.join(path).unwrap().as_str())
+    pub fn set_server_version(&mut self, server_version: Option<Version>) {
+        self.server_version = server_version;
+    }
+
+    /// Sends the request on the runtime and turns non-success responses into errors.
+    ///
+    /// `model` is the model the request refers to, so that a 404 can be reported as
+    /// [`Error::ModelNotFound`].
+    async fn send(&self, request: RequestBuilder, model: Option<&str>) -> Result<Response, Error> {
+        self.http
+            .send(request, |status, text| {
+                Error::from_status(status, text, model)
+            })
+            .await
+    }
+
+    /// Posts a request to an endpoint that streams NDJSON unless `stream` is disabled.
+    async fn post_stream<T>(
+        &self,
+        path: &'static str,
+        body: &impl Serialize,
+        model: &str,
+        streaming: bool,
+    ) -> Result<BoxStream<'static, Result<T, Error>>, Error>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let accept = if streaming {
+            "application/x-ndjson"
+        } else {
+            "application/json"
+        };
+
+        let resp = self
+            .send(
+                self.http.post(path).header(ACCEPT, accept).json(body),
+                Some(model),
+            )
+            .await?;
+
+        if !streaming {
+            let result = self.http.json::<T>(resp).await?;
+            return Ok(stream::once(async move { Ok(result) }).boxed());
+        }
+
+        Ok(self.ndjson(resp))
+    }
+
+    /// Reads an NDJSON response body on the runtime and forwards each decoded line.
+    ///
+    /// The body is read by a task that is aborted as soon as the returned stream is dropped.
+    fn ndjson<T>(&self, resp: Response) -> BoxStream<'static, Result<T, Error>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let (tx, mut rx) = mpsc::channel(32);
+
+        let task = self.http.spawn(async move {
+            let stream = resp.bytes_stream().map_err(io::Error::other);
+
+            let reader = StreamReader::new(stream);
+            let mut lines = FramedRead::new(reader, LinesCodec::new());
+
+            while let Some(line) = lines.next().await {
+                let item = match line {
+                    Ok(line) if line.trim().is_empty() => continue,
+                    Ok(line) => match serde_json::from_str::<ErrorResponse>(&line) {
+                        Ok(ErrorResponse { error }) => Err(Error::Stream(error)),
+                        Err(_) => serde_json::from_str::<T>(&line).map_err(Error::from),
+                    },
+                    Err(err) => Err(err.into()),
+                };
+
+                if tx.send(item).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        stream::poll_fn(move |cx| {
+            let _task = &task;
+            rx.poll_recv(cx)
+        })
+        .boxed()
     }
 }
 
 impl Client {
-    pub async fn version(&self) -> anyhow::Result<Version> {
+    pub async fn version(&self) -> Result<Version, Error> {
         #[derive(Deserialize)]
         struct VersionResponse {
             version: String,
         }
 
-        let request = self.get("api/version");
-
-        let version = self
-            .handle
-            .spawn(async { request.send().await })
-            .await??
-            .json::<VersionResponse>()
-            .await?;
+        let resp = self.send(self.http.get("api/version"), None).await?;
+        let version = self.http.json::<VersionResponse>(resp).await?;
 
-        Version::parse(&version.version).map_err(|err| anyhow!(err))
+        Ok(Version::parse(&version.version)?)
     }
 
-    pub async fn list(&self) -> anyhow::Result<Vec<Model>> {
-        let request = self.get("api/tags");
-
-        let models = self
-            .handle
-            .spawn(async { request.send().await })
-            .await??
-            .json::<ListResponse>()
-            .await?
-            .models;
+    pub async fn list(&self) -> Result<Vec<Model>, Error> {
+        let resp = self.send(self.http.get("api/tags"), None).await?;
 
-        Ok(models)
+        Ok(self.http.json::<ListResponse>(resp).await?.models)
     }
-}
 
-static DEFAULT_CLIENT: LazyLock<Client> = LazyLock::new(|| Client::default());
+    /// Lists the models that are currently loaded into memory.
+    pub async fn ps(&self) -> Result<Vec<RunningModel>, Error> {
+        let resp = self.send(self.http.get("api/ps"), None).await?;
 
-pub async fn version() -> anyhow::Result<Version> {
-    DEFAULT_CLIENT.version().await
-}
+        Ok(self.http.json::<PsResponse>(resp).await?.models)
+    }
+
+    pub async fn show(&self, show_request: ShowRequest) -> Result<ShowResponse, Error> {
+        let request = self.http.post("api/show").json(&show_request);
+        let resp = self.send(request, Some(&show_request.model)).await?;
 
-pub async fn list() -> anyhow::Result<Vec<Model>> {
-    DEFAULT_CLIENT.list().await
+        self.http.json::<ShowResponse>(resp).await
+    }
 }
 
 impl Client {
     pub async fn generate(
         &self,
-        generate_request: GenerateRequest,
-    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<GenerateResponse>>> {
-        let request = self.post("/api/generate");
+        mut generate_request: GenerateRequest,
+    ) -> Result<BoxStream<'static, Result<GenerateResponse, Error>>, Error> {
+        if let Some(version) = &self.server_version {
+            generate_request.degrade(version);
+        }
 
         let streaming = generate_request.stream.is_none_or(|stream| stream);
-        let accept = if streaming {
-            "application/x-ndjson"
-        } else {
-            "application/json"
-        };
 
-        let resp = self
-            .handle
-            .spawn(async move {
-                request
-                    .header(ACCEPT, accept)
-                    .json(&generate_request)
-                    .send()
-                    .await
-            })
-            .await??;
+        self.post_stream(
+            "api/generate",
+            &generate_request,
+            &generate_request.model,
+            streaming,
+        )
+        .await
+    }
+}
+
+impl Client {
+    /// Loads the model into memory by sending a request without a prompt.
+    pub async fn load(&self, model: impl Into<String>) -> Result<(), Error> {
+        let request = GenerateRequest::builder().model(model).stream_off().build();
 
-        let status_code = resp.status();
-        if !status_code.is_success() {
-            let text = resp.text().await?;
-            anyhow::bail!("{}: {}", status_code, text);
+        let mut result = self.generate(request).await?;
+        while let Some(item) = result.next().await {
+            item?;
         }
 
-        if !streaming {
-            let result = resp.json::<GenerateResponse>().await?;
-            return Ok(stream::once(async move { Ok(result) }).boxed());
+        Ok(())
+    }
+
+    /// Unloads the model from memory by sending a request with `keep_alive: "0"`.
+    pub async fn unload(&self, model: impl Into<String>) -> Result<(), Error> {
+        let request = GenerateRequest::builder()
+            .model(model)
+            .keep_alive("0")
+            .stream_off()
+            .build();
+
+        let mut result = self.generate(request).await?;
+        while let Some(item) = result.next().await {
+            item?;
         }
 
-        let stream = resp
-            .bytes_stream()
-            .map_err(|err| io::Error::new(io::ErrorKind::Other, err));
+        Ok(())
+    }
+}
+
+impl Client {
+    pub async fn chat(
+        &self,
+        mut chat_request: ChatRequest,
+    ) -> Result<BoxStream<'static, Result<ChatResponse, Error>>, Error> {
+        if let Some(version) = &self.server_version {
+            chat_request.degrade(version);
+        }
 
-        let reader = StreamReader::new(stream);
-        let lines = FramedRead::new(reader, LinesCodec::new());
+        let streaming = chat_request.stream.is_none_or(|stream| stream);
 
-        Ok(lines
-            .filter_map(async |line| match line {
-                Ok(line) if line.trim().is_empty() => None,
-                Ok(line) => Some(
-                    serde_json::from_str::<GenerateResponse>(&line).map_err(|err| anyhow!(err)),
-                ),
-                Err(err) => Some(Err(anyhow!(err))),
-            })
-            .boxed())
+        self.post_stream("api/chat", &chat_request, &chat_request.model, streaming)
+            .await
     }
 }
 
-pub async fn generate(
-    generate_request: GenerateRequest,
-) -> anyhow::Result<BoxStream<'static, anyhow::Result<GenerateResponse>>> {
-    DEFAULT_CLIENT.generate(generate_request).await
+impl Client {
+    pub async fn pull(
+        &self,
+        pull_request: PullRequest,
+    ) -> Result<BoxStream<'static, Result<PullResponse, Error>>, Error> {
+        let streaming = pull_request.stream.is_none_or(|stream| stream);
+
+        self.post_stream("api/pull", &pull_request, &pull_request.model, streaming)
+            .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::RUNTIME;
     use crate::language::Language;
     use crate::prompt::Prompt;
+    use reqwest::StatusCode;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+    use tokio::time::timeout;
+
+    /// Starts a server that accepts one connection and, if `respond` is set, streams three
+    /// NDJSON lines and then stalls like a slow model. The receiver resolves once the client
+    /// closes the connection.
+    async fn stub_server(respond: bool) -> (Url, oneshot::Receiver<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 8192];
+
+            if respond {
+                let line = r#"{"model":"stub","created_at":"","response":"token","done":false}"#;
+                let chunk = format!("{:x}\r\n{line}\n\r\n", line.len() + 1);
+
+                assert!(socket.read(&mut buf).await.unwrap() > 0);
+                socket
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\n\
+                          Content-Type: application/x-ndjson\r\n\
+                          Transfer-Encoding: chunked\r\n\r\n",
+                    )
+                    .await
+                    .unwrap();
+                socket.write_all(chunk.repeat(3).as_bytes()).await.unwrap();
+            }
+
+            while let Ok(n) = socket.read(&mut buf).await
+                && n > 0
+            {}
+
+            tx.send(()).ok();
+        });
+
+        (url, rx)
+    }
+
+    /// Starts a server that answers one request with the given raw HTTP response.
+    async fn respond_once(response: &'static str) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 8192];
+
+            assert!(socket.read(&mut buf).await.unwrap() > 0);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        url
+    }
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Runs a future on the current thread outside any tokio runtime, like gpui's executor.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    fn stub_request() -> GenerateRequest {
+        GenerateRequest::builder()
+            .model("stub")
+            .prompt("Hello, world!")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_generate_cancel_stream() {
+        let (url, disconnected) = stub_server(true).await;
+
+        let mut result = Client::new(url).generate(stub_request()).await.unwrap();
+
+        for _ in 0..3 {
+            assert_eq!(result.next().await.unwrap().unwrap().response, "token");
+        }
+
+        drop(result);
+
+        timeout(Duration::from_secs(5), disconnected)
+            .await
+            .expect("server should see the disconnect")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_generate_cancel_request() {
+        let (url, disconnected) = stub_server(false).await;
+
+        let client = Client::new(url);
+        assert!(
+            timeout(Duration::from_millis(100), client.generate(stub_request()))
+                .await
+                .is_err()
+        );
+
+        timeout(Duration::from_secs(5), disconnected)
+            .await
+            .expect("server should see the disconnect")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_parse_host() {
+        let parse = |host| parse_host(host).unwrap().to_string();
+
+        assert_eq!(parse("127.0.0.1"), "http://127.0.0.1:11434/");
+        assert_eq!(parse("0.0.0.0"), "http://127.0.0.1:11434/");
+        assert_eq!(parse("ollama.lan:8080"), "http://ollama.lan:8080/");
+        assert_eq!(parse("[::1]"), "http://[::1]:11434/");
+        assert_eq!(parse(" http://ollama.lan "), "http://ollama.lan/");
+        assert_eq!(
+            parse("https://ollama.lan/ollama"),
+            "https://ollama.lan/ollama"
+        );
+        assert!(parse_host("http://").is_err());
+    }
+
+    #[test]
+    fn test_client_base_url() {
+        let client = Client::new("https://ollama.lan/ollama".parse().unwrap());
+
+        assert_eq!(
+            client.base_url().join("api/tags").unwrap().as_str(),
+            "https://ollama.lan/ollama/api/tags"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_connect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let err = Client::new(url.parse().unwrap())
+            .version()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Connect(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn test_connection_headers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 8192];
+
+            let n = socket.read(&mut buf).await.unwrap();
+            tx.send(String::from_utf8_lossy(&buf[..n]).into_owned())
+                .ok();
+
+            socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\n\
+                      Content-Type: application/json\r\n\
+                      Connection: close\r\n\r\n\
+                      {\"version\":\"0.12.0\"}",
+                )
+                .await
+                .unwrap();
+        });
+
+        let options = ConnectionOptions {
+            headers: [("Authorization".to_owned(), "Bearer secret".to_owned())].into(),
+            ..Default::default()
+        };
+        let client = Client::with_options(url.parse().unwrap(), &options).unwrap();
+
+        assert_eq!(client.version().await.unwrap(), Version::new(0, 12, 0));
+
+        let request = rx.await.unwrap().to_lowercase();
+        assert!(
+            request.contains("authorization: bearer secret"),
+            "{request}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_read_timeout() {
+        let (url, _) = stub_server(false).await;
+
+        let options = ConnectionOptions {
+            read_timeout: Some(1),
+            ..Default::default()
+        };
+        let err = Client::with_options(url, &options)
+            .unwrap()
+            .version()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout(_)), "{err:?}");
+    }
+
+    /// With a read timeout, reqwest reads bodies with a tokio timer, so they have to be read on
+    /// the runtime even when the caller awaits on another executor.
+    #[test]
+    fn test_read_timeout_outside_runtime() {
+        let options = ConnectionOptions {
+            read_timeout: Some(5),
+            ..Default::default()
+        };
+
+        let url = RUNTIME.block_on(respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"version\":\"0.12.0\"}",
+        ));
+        let client = Client::with_options(url, &options).unwrap();
+        assert_eq!(block_on(client.version()).unwrap(), Version::new(0, 12, 0));
+
+        let url = RUNTIME.block_on(respond_once(
+            "HTTP/1.1 500 Internal Server Error\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"error\":\"out of memory\"}",
+        ));
+        let client = Client::with_options(url, &options).unwrap();
+        let err = block_on(client.list()).unwrap_err();
+        assert!(
+            matches!(&err, Error::Status { message, .. } if message == "out of memory"),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_status() {
+        let url = respond_once(
+            "HTTP/1.1 404 Not Found\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"error\":\"model 'stub' not found\"}",
+        )
+        .await;
+
+        let err = Client::new(url)
+            .generate(stub_request())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ModelNotFound(model) if model == "stub"));
+
+        // A wrong base path is a 404 too, but not a missing model.
+        let url = respond_once(
+            "HTTP/1.1 404 Not Found\r\n\
+             Content-Type: text/plain\r\n\
+             Connection: close\r\n\r\n\
+             404 page not found",
+        )
+        .await;
+
+        let err = Client::new(url)
+            .generate(stub_request())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            Error::Status { status, message }
+                if status == StatusCode::NOT_FOUND && message == "404 page not found"
+        ));
+
+        let url = respond_once(
+            "HTTP/1.1 500 Internal Server Error\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"error\":\"out of memory\"}",
+        )
+        .await;
+
+        let err = Client::new(url).list().await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Status { status, message }
+                if status == StatusCode::INTERNAL_SERVER_ERROR && message == "out of memory"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_error_stream() {
+        let url = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/x-ndjson\r\n\
+             Connection: close\r\n\r\n\
+             {\"model\":\"stub\",\"created_at\":\"\",\"response\":\"token\",\"done\":false}\n\
+             {\"error\":\"unexpected EOF\"}\n\
+             not json\n",
+        )
+        .await;
+
+        let mut result = Client::new(url).generate(stub_request()).await.unwrap();
+
+        assert_eq!(result.next().await.unwrap().unwrap().response, "token");
+        assert!(matches!(
+            result.next().await.unwrap(),
+            Err(Error::Stream(message)) if message == "unexpected EOF"
+        ));
+        assert!(matches!(
+            result.next().await.unwrap(),
+            Err(Error::Decode(_))
+        ));
+        assert!(result.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chat() {
+        let url = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/x-ndjson\r\n\
+             Connection: close\r\n\r\n\
+             {\"model\":\"stub\",\"created_at\":\"\",\"message\":{\"role\":\"assistant\",\"content\":\"Hello\"},\"done\":false}\n\
+             {\"model\":\"stub\",\"created_at\":\"\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}\n",
+        )
+        .await;
+
+        let req = ChatRequest::builder()
+            .model("stub")
+            .user("Hello, world!")
+            .build();
+
+        let responses = Client::new(url)
+            .chat(req)
+            .await
+            .unwrap()
+            .map(|item| item.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].message.content, "Hello");
+        assert!(responses[1].done);
+    }
+
+    #[tokio::test]
+    async fn test_pull() {
+        let url = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/x-ndjson\r\n\
+             Connection: close\r\n\r\n\
+             {\"status\":\"pulling manifest\"}\n\
+             {\"status\":\"pulling 7cd4618c1faf\",\"digest\":\"sha256:7cd4618c1faf\",\"total\":100,\"completed\":50}\n\
+             {\"status\":\"success\"}\n",
+        )
+        .await;
+
+        let statuses = Client::new(url)
+            .pull(PullRequest::new("stub"))
+            .await
+            .unwrap()
+            .map(|item| item.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(statuses.len(), 3);
+        assert_eq!(statuses[1].completed, Some(50));
+        assert_eq!(statuses[2].status, "success");
+    }
 
     #[tokio::test]
     #[ignore]
     async fn test_version() {
-        println!("{:#?}", version().await);
+        println!("{:#?}", Client::default().version().await);
     }
 
     #[tokio::test]
     #[ignore]
     async fn test_list() {
-        println!("{:#?}", list().await);
+        println!("{:#?}", Client::default().list().await);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_ps() {
+        println!("{:#?}", Client::default().ps().await);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_show() {
+        println!(
+            "{:#?}",
+            Client::default()
+                .show(ShowRequest::new("translategemma"))
+                .await
+        );
     }
 
     #[tokio::test]
@@ -184,7 +737,7 @@ mod tests {
             .prompt(prompt.to_string())
             .build();
 
-        let mut result = generate(req).await.unwrap();
+        let mut result = Client::default().generate(req).await.unwrap();
 
         while let Some(item) = result.next().await {
             println!("{}", item.unwrap().response);
@@ -0,0 +1,411 @@
+use crate::backoff::Backoff;
+use crate::http::{AbortOnDrop, RUNTIME};
+use std::collections::VecDeque;
+use std::env;
+use std::future::{poll_fn, Future};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+
+/// The number of log lines kept from `ollama serve`.
+const MAX_LOG_LINES: usize = 1000;
+/// How soon to restart the server after the first crash.
+const INITIAL_RESTART_DELAY: Duration = Duration::from_secs(1);
+/// The longest delay between restarts when the server keeps crashing.
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+/// A server that ran at least this long is considered to have started successfully.
+const STABLE_UPTIME: Duration = Duration::from_secs(30);
+
+/// How to run `ollama serve`.
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub binary: PathBuf,
+    /// Passed as `OLLAMA_HOST`, e.g. `127.0.0.1:11434`.
+    pub host: Option<String>,
+    /// Passed as `OLLAMA_MODELS`.
+    pub models_dir: Option<PathBuf>,
+}
+
+impl ServeOptions {
+    pub fn new(binary: impl Into<PathBuf>) -> Self {
+        ServeOptions {
+            binary: binary.into(),
+            host: None,
+            models_dir: None,
+        }
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.binary);
+        command
+            .arg("serve")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        if let Some(host) = &self.host {
+            command.env("OLLAMA_HOST", host);
+        }
+
+        if let Some(models_dir) = &self.models_dir {
+            command.env("OLLAMA_MODELS", models_dir);
+        }
+
+        command
+    }
+}
+
+/// The running `ollama serve`, shared with [`Server`] so that it can be killed right away rather
+/// than whenever the aborted task gets to drop it.
+#[derive(Default)]
+struct Process {
+    child: Option<Child>,
+    stopped: bool,
+}
+
+impl Process {
+    /// Keeps the child, or kills it if the server has been stopped meanwhile. Returns `false` in
+    /// that case.
+    fn start(&mut self, mut child: Child) -> bool {
+        if self.stopped {
+            child.start_kill().ok();
+            return false;
+        }
+
+        self.child = Some(child);
+        true
+    }
+
+    /// Kills the child, returning it to wait for.
+    fn stop(&mut self) -> Option<Child> {
+        self.stopped = true;
+
+        let mut child = self.child.take()?;
+        child.start_kill().ok();
+        Some(child)
+    }
+}
+
+/// Runs `ollama serve` and restarts it when it exits. Dropping the server kills the process.
+pub struct Server {
+    logs: Arc<Mutex<VecDeque<String>>>,
+    process: Arc<Mutex<Process>>,
+    _task: AbortOnDrop<()>,
+}
+
+impl Server {
+    pub fn spawn(options: ServeOptions) -> Self {
+        let logs = Arc::new(Mutex::new(VecDeque::new()));
+        let process = Arc::new(Mutex::new(Process::default()));
+        let task = AbortOnDrop(RUNTIME.spawn(supervise(options, logs.clone(), process.clone())));
+
+        Server {
+            logs,
+            process,
+            _task: task,
+        }
+    }
+
+    /// Returns the most recent log lines, oldest first.
+    pub fn logs(&self) -> Vec<String> {
+        self.logs.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Kills the process right away, and returns a future that resolves once it has exited. It
+    /// can be awaited on any executor.
+    pub fn stop(self) -> impl Future<Output = ()> + use<> {
+        let child = self.process.lock().unwrap().stop();
+        let exited = RUNTIME.spawn(async move {
+            if let Some(mut child) = child {
+                child.wait().await.ok();
+            }
+        });
+
+        async move {
+            exited.await.ok();
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.process.lock().unwrap().stop();
+    }
+}
+
+/// Waits for the child to exit without holding the lock in between, so that the server can
+/// kill it meanwhile. Returns `None` once the server has been stopped.
+async fn wait(process: &Mutex<Process>) -> Option<io::Result<ExitStatus>> {
+    poll_fn(|cx| {
+        let mut process = process.lock().unwrap();
+        let Some(child) = process.child.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        // Waiting is cancel safe, the child keeps track of its exit.
+        let result = ready!(pin!(child.wait()).poll(cx));
+        process.child = None;
+
+        Poll::Ready(Some(result))
+    })
+    .await
+}
+
+async fn supervise(
+    options: ServeOptions,
+    logs: Arc<Mutex<VecDeque<String>>>,
+    process: Arc<Mutex<Process>>,
+) {
+    let mut backoff = Backoff::new(INITIAL_RESTART_DELAY, MAX_RESTART_DELAY);
+
+    loop {
+        log(
+            &logs,
+            format!("starting {} serve", options.binary.display()),
+        );
+        let started_at = Instant::now();
+
+        match options.command().spawn() {
+            Ok(mut child) => {
+                capture(&mut child, &logs);
+
+                if !process.lock().unwrap().start(child) {
+                    return;
+                }
+
+                match wait(&process).await {
+                    Some(Ok(status)) => log(&logs, format!("ollama serve exited with {status}")),
+                    Some(Err(err)) => log(&logs, format!("failed to wait for ollama serve: {err}")),
+                    None => return,
+                }
+            }
+            Err(err) => log(&logs, format!("failed to start ollama serve: {err}")),
+        }
+
+        if started_at.elapsed() >= STABLE_UPTIME {
+            backoff.reset();
+        }
+
+        tokio::time::sleep(backoff.next_delay()).await;
+    }
+}
+
+/// Copies the output of the child into the log until its pipes are closed.
+fn capture(child: &mut Child, logs: &Arc<Mutex<VecDeque<String>>>) {
+    fn forward(
+        output: impl AsyncRead + Unpin + Send + 'static,
+        logs: Arc<Mutex<VecDeque<String>>>,
+    ) {
+        RUNTIME.spawn(async move {
+            let mut lines = BufReader::new(output).lines();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                log(&logs, line);
+            }
+        });
+    }
+
+    if let Some(stdout) = child.stdout.take() {
+        forward(stdout, logs.clone());
+    }
+
+    if let Some(stderr) = child.stderr.take() {
+        forward(stderr, logs.clone());
+    }
+}
+
+fn log(logs: &Mutex<VecDeque<String>>, line: String) {
+    let mut logs = logs.lock().unwrap();
+
+    if logs.len() == MAX_LOG_LINES {
+        logs.pop_front();
+    }
+    logs.push_back(line);
+}
+
+/// Finds the `ollama` binary, preferring the configured path, then `PATH`, then the default
+/// install locations, which GUI apps may not have on their `PATH`.
+pub fn find_binary(configured: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = configured {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let paths = env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    find_binary_in(paths.into_iter().chain(default_install_dirs()))
+}
+
+fn find_binary_in(dirs: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    let name = format!("ollama{}", env::consts::EXE_SUFFIX);
+
+    dirs.into_iter()
+        .map(|dir| dir.join(&name))
+        .find(|path| path.is_file())
+}
+
+fn default_install_dirs() -> Vec<PathBuf> {
+    if cfg!(windows) {
+        env::var_os("LOCALAPPDATA")
+            .map(|dir| vec![PathBuf::from(dir).join("Programs").join("Ollama")])
+            .unwrap_or_default()
+    } else {
+        vec![
+            PathBuf::from("/usr/local/bin"),
+            PathBuf::from("/opt/homebrew/bin"),
+            PathBuf::from("/usr/bin"),
+        ]
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Creates an empty directory for a test under the system temp directory.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Writes an executable shell script named `ollama` that stands in for the real binary.
+    fn stub(dir: &Path, script: &str) -> PathBuf {
+        let path = dir.join("ollama");
+        fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    async fn wait_for(server: &Server, predicate: impl Fn(&[String]) -> bool) -> Vec<String> {
+        for _ in 0..100 {
+            let logs = server.logs();
+            if predicate(&logs) {
+                return logs;
+            }
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        panic!("timed out waiting for logs: {:#?}", server.logs());
+    }
+
+    #[test]
+    fn test_find_binary() {
+        let dir = temp_dir("test-find-binary");
+        let binary = stub(&dir, "");
+
+        assert_eq!(
+            find_binary_in([dir.join("missing"), dir.clone()]),
+            Some(binary.clone())
+        );
+        assert_eq!(find_binary_in([dir.join("missing")]), None);
+
+        assert_eq!(find_binary(Some(&binary)), Some(binary));
+        assert_eq!(find_binary(Some(&dir.join("missing"))), None);
+    }
+
+    #[tokio::test]
+    async fn test_serve_options() {
+        let dir = temp_dir("test-serve-options");
+        let binary = stub(
+            &dir,
+            r#"echo "$1 $OLLAMA_HOST $OLLAMA_MODELS"; echo oops >&2"#,
+        );
+
+        let server = Server::spawn(ServeOptions {
+            binary,
+            host: Some("127.0.0.1:11435".to_owned()),
+            models_dir: Some(dir.join("models")),
+        });
+
+        let logs = wait_for(&server, |logs| {
+            logs.iter().any(|line| line.starts_with("serve"))
+                && logs.iter().any(|line| line == "oops")
+        })
+        .await;
+
+        let models_dir = dir.join("models");
+        assert!(logs.contains(&format!("serve 127.0.0.1:11435 {}", models_dir.display())));
+    }
+
+    #[tokio::test]
+    async fn test_restart() {
+        let dir = temp_dir("test-restart");
+        let binary = stub(&dir, "echo started; exit 1");
+
+        let server = Server::spawn(ServeOptions::new(binary));
+
+        let logs = wait_for(&server, |logs| {
+            logs.iter().filter(|line| *line == "started").count() >= 2
+        })
+        .await;
+
+        assert!(logs.iter().any(|line| line.contains("exited with")));
+    }
+
+    /// Starts a stub server that runs until it is killed, and returns it with its PID.
+    async fn spawn_long_running(name: &str) -> (Server, String) {
+        let dir = temp_dir(name);
+        let pid_file = dir.join("pid");
+        let binary = stub(
+            &dir,
+            &format!(
+                "echo $$ > {}; echo ready; exec sleep 60",
+                pid_file.display()
+            ),
+        );
+
+        let server = Server::spawn(ServeOptions::new(binary));
+        wait_for(&server, |logs| logs.iter().any(|line| line == "ready")).await;
+
+        let pid = fs::read_to_string(&pid_file).unwrap().trim().to_owned();
+        (server, pid)
+    }
+
+    fn is_alive(pid: &str) -> bool {
+        std::process::Command::new("kill")
+            .args(["-0", pid])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap()
+            .success()
+    }
+
+    #[tokio::test]
+    async fn test_kill_on_drop() {
+        let (server, pid) = spawn_long_running("test-kill-on-drop").await;
+        assert!(is_alive(&pid));
+
+        drop(server);
+
+        for _ in 0..100 {
+            if !is_alive(&pid) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        panic!("ollama serve was not killed");
+    }
+
+    #[tokio::test]
+    async fn test_stop() {
+        let (server, pid) = spawn_long_running("test-stop").await;
+        assert!(is_alive(&pid));
+
+        server.stop().await;
+        assert!(!is_alive(&pid), "ollama serve is still running");
+    }
+}
@@ -0,0 +1,240 @@
+use crate::ollama::{Format, Metrics, Reasoning, ReasoningEffort};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::skip_serializing_none;
+use std::collections::HashMap;
+
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<Message>,
+    pub format: Option<Format>,
+    /// Streaming is enabled by default.
+    pub stream: Option<bool>,
+    /// Reasoning control: boolean toggle or effort level.
+    #[serde(rename = "think")]
+    pub reasoning: Option<Reasoning>,
+
+    /// How long to keep the model loaded after the request (default: "5m").
+    pub keep_alive: Option<String>,
+    pub options: Option<HashMap<String, Value>>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+    pub images: Option<Vec<Vec<u8>>>,
+    /// The model's reasoning, returned when reasoning is enabled.
+    pub thinking: Option<String>,
+}
+
+#[allow(dead_code)]
+impl Message {
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Message {
+            role,
+            content: content.into(),
+            images: None,
+            thinking: None,
+        }
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Message::new(Role::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Message::new(Role::User, content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Message::new(Role::Assistant, content)
+    }
+
+    pub fn image(mut self, image: impl Into<Vec<u8>>) -> Self {
+        self.images.get_or_insert_with(Vec::new).push(image.into());
+        self
+    }
+}
+
+impl ChatRequest {
+    pub fn builder() -> ChatRequestBuilder {
+        ChatRequestBuilder(Default::default())
+    }
+}
+
+pub struct ChatRequestBuilder(ChatRequest);
+
+#[allow(dead_code)]
+impl ChatRequestBuilder {
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.0.model = model.into();
+        self
+    }
+
+    pub fn message(mut self, message: Message) -> Self {
+        self.0.messages.push(message);
+        self
+    }
+
+    pub fn messages(mut self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.0.messages.extend(messages);
+        self
+    }
+
+    pub fn system(self, content: impl Into<String>) -> Self {
+        self.message(Message::system(content))
+    }
+
+    pub fn user(self, content: impl Into<String>) -> Self {
+        self.message(Message::user(content))
+    }
+
+    pub fn assistant(self, content: impl Into<String>) -> Self {
+        self.message(Message::assistant(content))
+    }
+
+    pub fn json(mut self) -> Self {
+        self.0.format = Some(Format::Json);
+        self
+    }
+
+    pub fn json_schema(mut self, schema: Value) -> Self {
+        self.0.format = Some(Format::JSONSchema(schema));
+        self
+    }
+
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.0.stream = Some(stream);
+        self
+    }
+
+    pub fn stream_on(self) -> Self {
+        self.stream(true)
+    }
+
+    pub fn stream_off(self) -> Self {
+        self.stream(false)
+    }
+
+    pub fn reasoning(mut self, reasoning: bool) -> Self {
+        self.0.reasoning = Some(Reasoning::Reasoning(reasoning));
+        self
+    }
+
+    pub fn reasoning_effort(mut self, reasoning_effort: ReasoningEffort) -> Self {
+        self.0.reasoning = Some(Reasoning::ReasoningEffort(reasoning_effort));
+        self
+    }
+
+    pub fn reasoning_on(self) -> Self {
+        self.reasoning(true)
+    }
+
+    pub fn reasoning_off(self) -> Self {
+        self.reasoning(false)
+    }
+
+    pub fn reasoning_low(self) -> Self {
+        self.reasoning_effort(ReasoningEffort::Low)
+    }
+
+    pub fn reasoning_medium(self) -> Self {
+        self.reasoning_effort(ReasoningEffort::Medium)
+    }
+
+    pub fn reasoning_high(self) -> Self {
+        self.reasoning_effort(ReasoningEffort::High)
+    }
+
+    pub fn keep_alive(mut self, keep_alive: impl Into<String>) -> Self {
+        self.0.keep_alive = Some(keep_alive.into());
+        self
+    }
+
+    pub fn option(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.0
+            .options
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
+
+        self
+    }
+
+    pub fn build(self) -> ChatRequest {
+        self.0
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct ChatResponse {
+    pub model: String,
+    pub created_at: String,
+    pub message: Message,
+    pub done: bool,
+    pub done_reason: Option<String>,
+    #[serde(flatten)]
+    pub metrics: Metrics,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_chat_request() {
+        let req = ChatRequest::builder()
+            .model("translategemma")
+            .system("You are a translator.")
+            .user("Hello")
+            .assistant("你好")
+            .json()
+            .stream_off()
+            .reasoning_low()
+            .option("temperature", 0)
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(&req).unwrap(),
+            json!({
+                "model": "translategemma",
+                "messages": [
+                    { "role": "system", "content": "You are a translator." },
+                    { "role": "user", "content": "Hello" },
+                    { "role": "assistant", "content": "你好" },
+                ],
+                "format": "json",
+                "stream": false,
+                "think": "low",
+                "options": { "temperature": 0 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_chat_response() {
+        let resp = serde_json::from_str::<ChatResponse>(
+            r#"{"model":"translategemma","created_at":"2026-01-01T00:00:00Z","message":{"role":"assistant","content":"你好"},"done":true,"done_reason":"stop","eval_count":2}"#,
+        )
+        .unwrap();
+
+        assert_eq!(resp.message.role, Role::Assistant);
+        assert_eq!(resp.message.content, "你好");
+        assert_eq!(resp.done_reason.as_deref(), Some("stop"));
+        assert_eq!(resp.metrics.eval_count, Some(2));
+    }
+}
@@ -4,7 +4,7 @@ use serde_with::skip_serializing_none;
 use std::collections::HashMap;
 
 #[skip_serializing_none]
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct GenerateRequest {
     pub model: String,
     pub prompt: String,
@@ -24,22 +24,22 @@ pub struct GenerateRequest {
     pub options: Option<HashMap<String, Value>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Format {
     #[serde(rename = "json")]
-    JSON,
+    Json,
     #[serde(untagged)]
     JSONSchema(Value),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum Reasoning {
     Reasoning(bool),
     ReasoningEffort(ReasoningEffort),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ReasoningEffort {
     High,
@@ -82,7 +82,7 @@ impl GenerateRequestBuilder {
     }
 
     pub fn json(mut self) -> Self {
-        self.0.format = Some(Format::JSON);
+        self.0.format = Some(Format::Json);
         self
     }
 
@@ -144,6 +144,16 @@ impl GenerateRequestBuilder {
         self
     }
 
+    pub fn keep_alive(mut self, keep_alive: impl Into<String>) -> Self {
+        self.0.keep_alive = Some(keep_alive.into());
+        self
+    }
+
+    pub fn options(mut self, options: HashMap<String, Value>) -> Self {
+        self.0.options = Some(options);
+        self
+    }
+
     pub fn build(self) -> GenerateRequest {
         self.0
     }
@@ -180,7 +190,7 @@ mod tests {
 
     #[test]
     fn test_format() {
-        println!("{:?}", serde_json::to_string_pretty(&Format::JSON).unwrap());
+        println!("{:?}", serde_json::to_string_pretty(&Format::Json).unwrap());
         println!(
             "{:?}",
             serde_json::to_string(&Format::JSONSchema(json!({
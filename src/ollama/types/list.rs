@@ -6,7 +6,7 @@ pub struct ListResponse {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Model {
     pub name: String,
     pub modified_at: String,
@@ -16,11 +16,11 @@ pub struct Model {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ModelDetails {
     pub format: String,
     pub family: String,
-    pub families: Vec<String>,
+    pub families: Option<Vec<String>>,
     pub parameter_size: String,
     pub quantization_level: String,
 }
@@ -1,5 +1,13 @@
+mod chat;
 mod generate;
 mod list;
+mod ps;
+mod pull;
+mod show;
 
+pub use chat::*;
 pub use generate::*;
 pub use list::*;
+pub use ps::*;
+pub use pull::*;
+pub use show::*;
@@ -0,0 +1,133 @@
+use crate::ollama::ModelDetails;
+use serde::Deserialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Deserialize)]
+pub struct PsResponse {
+    pub models: Vec<RunningModel>,
+}
+
+/// A model that is currently loaded into memory.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunningModel {
+    pub name: String,
+    pub model: String,
+    /// Total memory used by the model, in bytes.
+    pub size: u64,
+    pub digest: String,
+    pub details: ModelDetails,
+    pub expires_at: String,
+    /// Memory used on the GPU, in bytes.
+    pub size_vram: u64,
+    pub context_length: Option<u64>,
+}
+
+impl RunningModel {
+    /// Returns how long until the model is unloaded, or `None` if `expires_at` cannot be parsed
+    /// or is in the past.
+    pub fn expires_in(&self) -> Option<Duration> {
+        parse_rfc3339(&self.expires_at)?
+            .duration_since(SystemTime::now())
+            .ok()
+    }
+
+    /// Returns the share of the model that is loaded on the GPU, from 0 to 100.
+    pub fn gpu_percentage(&self) -> u64 {
+        (self.size_vram.min(self.size) * 100)
+            .checked_div(self.size)
+            .unwrap_or_default()
+    }
+}
+
+/// Parses an RFC 3339 timestamp such as `2024-06-04T14:38:31.837-07:00`, ignoring fractional
+/// seconds.
+fn parse_rfc3339(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.split_once(['T', 't', ' '])?;
+
+    let mut date = date.splitn(3, '-');
+    let year = date.next()?.parse::<i64>().ok()?;
+    let month = date.next()?.parse::<i64>().ok()?;
+    let day = date.next()?.parse::<i64>().ok()?;
+
+    let (time, offset) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let (time, offset) = time.split_at(time.rfind(['+', '-'])?);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':')?;
+
+        (
+            time,
+            sign * (hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60),
+        )
+    };
+
+    let mut time = time.split('.').next()?.splitn(3, ':');
+    let hours = time.next()?.parse::<i64>().ok()?;
+    let minutes = time.next()?.parse::<i64>().ok()?;
+    let seconds = time.next()?.parse::<i64>().ok()?;
+
+    let secs =
+        days_from_civil(year, month, day) * 86400 + hours * 3600 + minutes * 60 + seconds - offset;
+
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+/// Returns the number of days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let secs = |timestamp| {
+            parse_rfc3339(timestamp)
+                .unwrap()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        };
+
+        assert_eq!(secs("1970-01-01T00:00:00Z"), 0);
+        assert_eq!(secs("2026-01-01T00:00:00Z"), 1767225600);
+        assert_eq!(secs("2024-06-04T14:38:31.837-07:00"), 1717537111);
+        assert_eq!(secs("2024-06-05T05:38:31+08:00"), 1717537111);
+        assert!(parse_rfc3339("not a timestamp").is_none());
+    }
+
+    #[test]
+    fn test_running_model() {
+        let model = serde_json::from_str::<RunningModel>(
+            r#"{
+                "name": "translategemma:27b",
+                "model": "translategemma:27b",
+                "size": 20000000000,
+                "digest": "sha256:0",
+                "details": {
+                    "format": "gguf",
+                    "family": "gemma3",
+                    "families": null,
+                    "parameter_size": "27.4B",
+                    "quantization_level": "Q4_K_M"
+                },
+                "expires_at": "2318-01-01T00:00:00Z",
+                "size_vram": 15000000000
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(model.gpu_percentage(), 75);
+        assert!(model.expires_in().is_some());
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize)]
+pub struct PullRequest {
+    pub model: String,
+    /// Allows insecure connections to the registry.
+    pub insecure: Option<bool>,
+    /// Streaming is enabled by default.
+    pub stream: Option<bool>,
+}
+
+impl PullRequest {
+    pub fn new(model: impl Into<String>) -> Self {
+        PullRequest {
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A status line of a pull, e.g. `pulling manifest` or `pulling <digest>` with the download
+/// progress of that layer.
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct PullResponse {
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_response() {
+        let resp = serde_json::from_str::<PullResponse>(
+            r#"{"status":"pulling 7cd4618c1faf","digest":"sha256:7cd4618c1faf","total":3338801792,"completed":1048576}"#,
+        )
+        .unwrap();
+
+        assert_eq!(resp.total, Some(3338801792));
+        assert_eq!(resp.completed, Some(1048576));
+
+        let resp = serde_json::from_str::<PullResponse>(r#"{"status":"success"}"#).unwrap();
+
+        assert_eq!(resp.status, "success");
+        assert!(resp.digest.is_none());
+    }
+}
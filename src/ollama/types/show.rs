@@ -0,0 +1,84 @@
+use crate::ollama::ModelDetails;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::skip_serializing_none;
+use std::collections::HashMap;
+
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize)]
+pub struct ShowRequest {
+    pub model: String,
+    /// Includes large fields such as the tokenizer vocabulary in `model_info`.
+    pub verbose: Option<bool>,
+}
+
+impl ShowRequest {
+    pub fn new(model: impl Into<String>) -> Self {
+        ShowRequest {
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShowResponse {
+    pub license: Option<String>,
+    pub modelfile: Option<String>,
+    /// Modelfile parameters, one `name value` pair per line.
+    pub parameters: Option<String>,
+    pub template: Option<String>,
+    pub system: Option<String>,
+    pub details: ModelDetails,
+    #[serde(default)]
+    pub model_info: HashMap<String, Value>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    pub modified_at: Option<String>,
+}
+
+impl ShowResponse {
+    pub fn architecture(&self) -> Option<&str> {
+        self.model_info.get("general.architecture")?.as_str()
+    }
+
+    /// The maximum context length the model was trained with, e.g. `gemma3.context_length`.
+    pub fn context_length(&self) -> Option<u64> {
+        let key = format!("{}.context_length", self.architecture()?);
+        self.model_info.get(&key)?.as_u64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_show_response() {
+        let resp = serde_json::from_str::<ShowResponse>(
+            r#"{
+                "parameters": "stop \"<end_of_turn>\"\ntemperature 1",
+                "template": "{{ .Prompt }}",
+                "details": {
+                    "format": "gguf",
+                    "family": "gemma3",
+                    "families": ["gemma3"],
+                    "parameter_size": "4.3B",
+                    "quantization_level": "Q4_K_M"
+                },
+                "model_info": {
+                    "general.architecture": "gemma3",
+                    "gemma3.context_length": 131072
+                },
+                "capabilities": ["completion", "vision"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(resp.architecture(), Some("gemma3"));
+        assert_eq!(resp.context_length(), Some(131072));
+        assert_eq!(resp.capabilities, ["completion", "vision"]);
+        assert_eq!(resp.details.quantization_level, "Q4_K_M");
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::Config;
+use crate::ollama;
+use gpui::{div, prelude::*, App, Entity, Window};
+use gpui_component::input::{Input, InputState};
+use gpui_component::label::Label;
+use gpui_component::notification::Notification;
+use gpui_component::{ActiveTheme, WindowExt};
+
+pub fn open_ollama_host_dialog(config: Entity<Config>, window: &mut Window, cx: &mut App) {
+    let host = config.read(cx).ollama_host().cloned().unwrap_or_default();
+    let extra_hosts = config.read(cx).extra_ollama_hosts().join("\n");
+
+    let state = cx.new(|cx| {
+        InputState::new(window, cx)
+            .placeholder(ollama::default_base_url().to_string())
+            .default_value(host)
+    });
+    let extra_state = cx.new(|cx| {
+        InputState::new(window, cx)
+            .multi_line(true)
+            .rows(3)
+            .placeholder("http://192.168.1.11:11434")
+            .default_value(extra_hosts)
+    });
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let state = state.clone();
+        let extra_state = extra_state.clone();
+        let config = config.clone();
+
+        dialog
+            .confirm()
+            .title(t!("ollama-host").to_string())
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(Input::new(&state))
+                    .child(
+                        Label::new(t!("ollama-host.description"))
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(Label::new(t!("ollama-host.extra")).text_sm())
+                    .child(Input::new(&extra_state))
+                    .child(
+                        Label::new(t!("ollama-host.extra.description"))
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .on_ok(move |_, window, cx| {
+                let host = state.read(cx).value().trim().to_string();
+                let extra_hosts = extra_state
+                    .read(cx)
+                    .value()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|host| !host.is_empty())
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+
+                for host in extra_hosts
+                    .iter()
+                    .chain(Some(&host).filter(|host| !host.is_empty()))
+                {
+                    if let Err(err) = ollama::parse_host(host) {
+                        window.push_notification(
+                            Notification::error(format!(
+                                "{}: {host}: {err}",
+                                t!("ollama-host.invalid")
+                            )),
+                            cx,
+                        );
+                        return false;
+                    }
+                }
+
+                config.update(cx, |this, cx| {
+                    this.set_ollama_hosts(Some(host), extra_hosts, cx);
+                });
+
+                true
+            })
+    });
+}
@@ -1,79 +1,572 @@
-use crate::ollama;
-use gpui::{Context, EventEmitter};
+use crate::backend::{
+    AvailableModel, Backend, BackendKind, EndpointConfig, ServerInfo, TranslationBackend,
+};
+use crate::backoff::Backoff;
+use crate::model_filter::ModelFilter;
+use crate::ollama::{self, RunningModel, ShowRequest, ShowResponse};
+use futures_util::future::join_all;
+use gpui::{Context, EventEmitter, Task};
 use semver::Version;
+use std::mem;
 use std::time::Duration;
+use url::Url;
+
+/// How soon to retry after the first failed connection attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The longest delay between retries while disconnected.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    /// Waiting for the first response from the server.
+    Connecting,
+    Connected,
+    /// The server could not be reached, usually because Ollama is not running.
+    Disconnected(String),
+    /// The server was reached but the request failed.
+    Error(String),
+}
+
+/// One server, polled independently of the others.
+pub struct Endpoint {
+    pub base_url: Url,
+    pub state: ConnectionState,
+    /// The most recent connection error, kept after reconnecting.
+    pub last_error: Option<String>,
+    /// What the server reported in the last successful health check.
+    pub info: ServerInfo,
+    pub models: Vec<AvailableModel>,
+    /// Models currently loaded into memory, as reported by `/api/ps`. Only Ollama reports them.
+    pub running_models: Vec<RunningModel>,
+
+    backend: Backend,
+    /// Set when the connection settings are invalid, in which case the server is not polled.
+    options_error: Option<String>,
+    health_check: Option<Task<()>>,
+    models_polling: Option<Task<()>>,
+    running_models_polling: Option<Task<()>>,
+}
+
+impl Endpoint {
+    /// Falls back to a plain client when the options are invalid, so that requests fail
+    /// visibly instead of silently going out without the configured headers or proxy.
+    fn new(config: EndpointConfig) -> Self {
+        let (backend, options_error) = match Backend::from_config(&config) {
+            Ok(backend) => (backend, None),
+            Err(err) => (
+                Backend::new(config.kind, config.base_url.clone()),
+                Some(err.to_string()),
+            ),
+        };
+
+        Endpoint {
+            base_url: config.base_url,
+            state: ConnectionState::Connecting,
+            last_error: None,
+            info: ServerInfo::default(),
+            models: Vec::new(),
+            running_models: Vec::new(),
+            backend,
+            options_error,
+            health_check: None,
+            models_polling: None,
+            running_models_polling: None,
+        }
+    }
+
+    /// A short name for the server, e.g. `192.168.1.10:11434`.
+    pub fn label(&self) -> String {
+        match (
+            self.base_url.host_str(),
+            self.base_url.port_or_known_default(),
+        ) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            _ => self.base_url.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> BackendKind {
+        self.backend.kind()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.state == ConnectionState::Connected
+    }
+
+    fn has_model(&self, name: &str) -> bool {
+        self.models.iter().any(|model| model.name == name)
+    }
+
+    fn is_running(&self, name: &str) -> bool {
+        self.running_models.iter().any(|model| model.name == name)
+    }
+}
 
 pub struct OllamaService {
-    pub version: Option<Version>,
-    pub models: Vec<String>,
+    /// The configured servers, the main one first.
+    pub endpoints: Vec<Endpoint>,
+    /// The models of all servers, each listed once.
+    pub models: Vec<AvailableModel>,
+    /// The loaded models of all servers.
+    pub running_models: Vec<RunningModel>,
+
+    model_filter: ModelFilter,
+    /// How often to poll once connected.
+    poll_interval: Duration,
 }
 
 pub enum OllamaServiceEvent {
-    VersionChanged,
+    ConnectionChanged,
     ModelsChanged,
+    RunningModelsChanged,
 }
 
 impl EventEmitter<OllamaServiceEvent> for OllamaService {}
 
 impl OllamaService {
-    pub fn new() -> Self {
+    pub fn new(
+        endpoints: Vec<EndpointConfig>,
+        model_filter: ModelFilter,
+        poll_interval: Duration,
+    ) -> Self {
         OllamaService {
-            version: None,
+            endpoints: endpoints.into_iter().map(Endpoint::new).collect(),
             models: Vec::new(),
+            running_models: Vec::new(),
+            model_filter,
+            poll_interval,
         }
     }
 
+    /// Returns the client of the main server when it is an Ollama server, e.g. for pulling
+    /// models.
+    pub fn ollama_client(&self) -> Option<ollama::Client> {
+        self.endpoints.first()?.backend.ollama().cloned()
+    }
+
+    /// Returns the API the main server speaks, Ollama when there is no server.
+    pub fn kind(&self) -> BackendKind {
+        self.endpoints
+            .first()
+            .map(Endpoint::kind)
+            .unwrap_or_default()
+    }
+
+    /// Replaces the servers and polls them right away.
+    pub fn set_endpoints(&mut self, endpoints: Vec<EndpointConfig>, cx: &mut Context<Self>) {
+        // Dropping the old endpoints cancels their polling.
+        self.endpoints = endpoints.into_iter().map(Endpoint::new).collect();
+        cx.emit(OllamaServiceEvent::ConnectionChanged);
+
+        self.models.clear();
+        cx.emit(OllamaServiceEvent::ModelsChanged);
+
+        self.running_models.clear();
+        cx.emit(OllamaServiceEvent::RunningModelsChanged);
+
+        cx.notify();
+
+        self.start_polling(cx);
+    }
+
+    /// Applies the filter to the model list right away.
+    pub fn set_model_filter(&mut self, model_filter: ModelFilter, cx: &mut Context<Self>) {
+        self.model_filter = model_filter;
+        self.start_polling(cx);
+    }
+
+    pub fn set_poll_interval(&mut self, poll_interval: Duration, cx: &mut Context<Self>) {
+        self.poll_interval = poll_interval;
+        self.start_polling(cx);
+    }
+
+    /// Returns the state of the best-connected server, or of the main server when none is
+    /// connected. Without any server, it is disconnected.
+    pub fn state(&self) -> ConnectionState {
+        if self.is_connected() {
+            ConnectionState::Connected
+        } else if self
+            .endpoints
+            .iter()
+            .any(|endpoint| endpoint.state == ConnectionState::Connecting)
+        {
+            ConnectionState::Connecting
+        } else {
+            self.endpoints
+                .first()
+                .map(|endpoint| endpoint.state.clone())
+                .unwrap_or_else(|| ConnectionState::Disconnected("no server configured".to_owned()))
+        }
+    }
+
+    /// Returns the most relevant connection error, prefixed with the server when there are
+    /// several.
+    pub fn last_error(&self) -> Option<String> {
+        let endpoint = self
+            .endpoints
+            .iter()
+            .find(|endpoint| !endpoint.is_connected() && endpoint.last_error.is_some())
+            .or_else(|| {
+                self.endpoints
+                    .iter()
+                    .find(|endpoint| endpoint.last_error.is_some())
+            })?;
+        let error = endpoint.last_error.clone()?;
+
+        Some(if self.endpoints.len() > 1 {
+            format!("{}: {error}", endpoint.label())
+        } else {
+            error
+        })
+    }
+
+    /// Returns the oldest version among the connected servers, since features are only
+    /// available when every server supports them.
+    pub fn version(&self) -> Option<Version> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_connected())
+            .filter_map(|endpoint| endpoint.info.version.clone())
+            .min()
+    }
+
+    /// Returns the model and context size reported by the first connected server that serves a
+    /// single model, such as llama.cpp.
+    pub fn loaded_model(&self) -> Option<(String, Option<u64>)> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_connected())
+            .find_map(|endpoint| {
+                let model = endpoint.info.model.clone()?;
+                Some((model, endpoint.info.context_size))
+            })
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.endpoints.iter().any(Endpoint::is_connected)
+    }
+
+    pub fn model(&self, name: &str) -> Option<&AvailableModel> {
+        self.models.iter().find(|model| model.name == name)
+    }
+
+    /// Returns the labels of the servers that have the model.
+    pub fn locations(&self, name: &str) -> Vec<String> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.has_model(name))
+            .map(Endpoint::label)
+            .collect()
+    }
+
+    /// Returns the backends to try in turn for a request to the model. Connected servers with
+    /// the model already loaded come first, then the other servers with the model, so that
+    /// requests spread across servers as models are loaded. When no server has the model, the
+    /// main server is tried so that the request fails with a useful error. Without any server,
+    /// there is nothing to try.
+    pub fn backends_for(&self, model: &str) -> Vec<Backend> {
+        let rank = |endpoint: &Endpoint| match (endpoint.is_connected(), endpoint.is_running(model))
+        {
+            (true, true) => 0,
+            (true, false) => 1,
+            (false, _) => 2,
+        };
+
+        let mut endpoints = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.has_model(model))
+            .collect::<Vec<_>>();
+
+        if endpoints.is_empty() {
+            return self
+                .endpoints
+                .first()
+                .map(|endpoint| endpoint.backend.clone())
+                .into_iter()
+                .collect();
+        }
+
+        // The sort is stable, so servers keep their configured order within each rank.
+        endpoints.sort_by_key(|endpoint| rank(*endpoint));
+        endpoints
+            .into_iter()
+            .map(|endpoint| endpoint.backend.clone())
+            .collect()
+    }
+
+    /// Returns the first Ollama client to try for the model, for requests that only Ollama
+    /// supports.
+    fn ollama_client_for(&self, model: &str) -> Option<ollama::Client> {
+        self.backends_for(model)
+            .into_iter()
+            .find_map(|backend| backend.ollama().cloned())
+    }
+
+    /// Fetches the template, parameters and capabilities of a model.
+    pub fn show(
+        &self,
+        model: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ShowResponse, ollama::Error>> {
+        let model = model.into();
+        let Some(client) = self.ollama_client_for(&model) else {
+            return Task::ready(Err(ollama::Error::Unsupported("showing model information")));
+        };
+        let request = ShowRequest::new(model);
+
+        cx.spawn(async move |_, _| client.show(request).await)
+    }
+
+    /// Loads the model into memory so that the next translation starts without delay.
+    pub fn preload(
+        &self,
+        model: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<(), ollama::Error>> {
+        let model = model.into();
+        let Some(client) = self.ollama_client_for(&model) else {
+            // Other servers load models on demand.
+            return Task::ready(Ok(()));
+        };
+
+        cx.spawn(async move |this, cx| {
+            client.load(model).await?;
+            this.update(cx, |this, cx| this.refresh_running_models(cx))
+                .ok();
+
+            Ok(())
+        })
+    }
+
+    /// Unloads the model from every server it is loaded on.
+    pub fn unload(
+        &self,
+        model: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<(), ollama::Error>> {
+        let model = model.into();
+        let clients = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_running(&model))
+            .filter_map(|endpoint| endpoint.backend.ollama().cloned())
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |this, cx| {
+            let results = join_all(clients.iter().map(|client| client.unload(model.clone()))).await;
+            this.update(cx, |this, cx| this.refresh_running_models(cx))
+                .ok();
+
+            results.into_iter().collect()
+        })
+    }
+
+    /// Polls the running models right away, e.g. after loading or unloading a model.
+    fn refresh_running_models(&mut self, cx: &mut Context<Self>) {
+        for index in 0..self.endpoints.len() {
+            if self.endpoints[index].is_connected()
+                && let Some(task) = self.poll_running_models(index, cx)
+            {
+                self.endpoints[index].running_models_polling = Some(task);
+            }
+        }
+    }
+
+    /// Checks the connections and polls the models right away instead of waiting for the next
+    /// interval.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.start_polling(cx);
+    }
+
     pub fn start_polling(&mut self, cx: &mut Context<Self>) {
-        self.poll_version(cx);
-        self.poll_models(cx);
+        for index in 0..self.endpoints.len() {
+            self.start_endpoint_polling(index, cx);
+        }
     }
 
-    fn poll_version(&mut self, cx: &mut Context<Self>) {
-        cx.spawn(async |this, cx| {
+    fn start_endpoint_polling(&mut self, index: usize, cx: &mut Context<Self>) {
+        let endpoint = &mut self.endpoints[index];
+
+        if let Some(err) = &endpoint.options_error {
+            endpoint.state = ConnectionState::Error(err.clone());
+            endpoint.last_error = Some(err.clone());
+            endpoint.health_check = None;
+            endpoint.models_polling = None;
+            endpoint.running_models_polling = None;
+
+            cx.emit(OllamaServiceEvent::ConnectionChanged);
+            cx.notify();
+            return;
+        }
+
+        // Dropping the previous tasks cancels them.
+        self.endpoints[index].health_check = Some(self.check_health(index, cx));
+
+        // Otherwise the models are polled once the health check succeeds.
+        if self.endpoints[index].is_connected() {
+            self.poll_all_models(index, cx);
+        }
+    }
+
+    fn poll_all_models(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.endpoints[index].models_polling = Some(self.poll_models(index, cx));
+        self.endpoints[index].running_models_polling = self.poll_running_models(index, cx);
+    }
+
+    /// Polls the health, retrying with backoff while disconnected and slowly once connected.
+    fn check_health(&mut self, index: usize, cx: &mut Context<Self>) -> Task<()> {
+        let backend = self.endpoints[index].backend.clone();
+        let poll_interval = self.poll_interval;
+
+        cx.spawn(async move |this, cx| {
+            let mut backoff = Backoff::new(INITIAL_RETRY_DELAY, MAX_RETRY_DELAY.min(poll_interval));
+
             loop {
-                let version = ollama::version().await.ok();
+                let result = backend.health().await;
+
+                let delay = if result.is_ok() {
+                    backoff.reset();
+                    poll_interval
+                } else {
+                    backoff.next_delay()
+                };
+
+                if this
+                    .update(cx, |this, cx| this.update_health(index, result, cx))
+                    .is_err()
+                {
+                    break;
+                }
+
+                cx.background_executor().timer(delay).await;
+            }
+        })
+    }
+
+    fn update_health(
+        &mut self,
+        index: usize,
+        result: Result<ServerInfo, ollama::Error>,
+        cx: &mut Context<Self>,
+    ) {
+        let endpoint = &mut self.endpoints[index];
+
+        match result {
+            Ok(info) => {
+                let reconnected = !endpoint.is_connected();
+
+                endpoint.state = ConnectionState::Connected;
+                if let Some(client) = endpoint.backend.ollama_mut() {
+                    client.set_server_version(info.version.clone());
+                }
+                endpoint.info = info;
+
+                if reconnected {
+                    self.poll_all_models(index, cx);
+                }
+            }
+            Err(err) => {
+                endpoint.state = if err.is_unreachable() {
+                    ConnectionState::Disconnected(err.to_string())
+                } else {
+                    ConnectionState::Error(err.to_string())
+                };
+                endpoint.last_error = Some(err.to_string());
+                endpoint.info = ServerInfo::default();
+
+                // Polling resumes after reconnecting.
+                endpoint.models_polling = None;
+                endpoint.running_models_polling = None;
+
+                // The models of an unreachable server cannot be used, so they are unlisted until
+                // it is back.
+                let had_models = !mem::take(&mut endpoint.models).is_empty();
+                let had_running_models = !mem::take(&mut endpoint.running_models).is_empty();
+
+                if had_models {
+                    self.merge_models(cx);
+                }
+
+                if had_running_models {
+                    self.merge_running_models(cx);
+                }
+            }
+        }
+
+        cx.emit(OllamaServiceEvent::ConnectionChanged);
+        cx.notify();
+    }
+
+    fn poll_models(&mut self, index: usize, cx: &mut Context<Self>) -> Task<()> {
+        let backend = self.endpoints[index].backend.clone();
+        let matches = self.model_filter.matcher();
+        let poll_interval = self.poll_interval;
+
+        cx.spawn(async move |this, cx| loop {
+            let models = backend.list_models().await.ok();
+
+            if let Some(models) = models {
+                let models = models
+                    .into_iter()
+                    .filter(|model| matches(&model.name))
+                    .collect::<Vec<_>>();
 
                 this.update(cx, |this, cx| {
-                    this.version = version;
-                    cx.emit(OllamaServiceEvent::VersionChanged);
-                    cx.notify();
+                    this.endpoints[index].models = models;
+                    this.merge_models(cx);
                 })
                 .ok();
-
-                cx.background_executor().timer(Duration::from_mins(5)).await;
             }
+
+            cx.background_executor().timer(poll_interval).await;
         })
-        .detach();
     }
 
-    fn poll_models(&mut self, cx: &mut Context<Self>) {
-        cx.spawn(async |this, cx| {
+    /// Returns `None` for servers other than Ollama, which do not report loaded models.
+    fn poll_running_models(&mut self, index: usize, cx: &mut Context<Self>) -> Option<Task<()>> {
+        let client = self.endpoints[index].backend.ollama()?.clone();
+
+        Some(cx.spawn(async move |this, cx| {
             loop {
-                let models = ollama::list().await.ok();
-
-                if let Some(models) = models {
-                    let models = models
-                        .into_iter()
-                        .filter_map(|model| {
-                            if model.name.starts_with("translategemma") {
-                                Some(model.name)
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<_>>();
-
-                    this.update(cx, |this, cx| {
-                        this.models = models;
-                        cx.emit(OllamaServiceEvent::ModelsChanged);
-                        cx.notify();
-                    })
-                    .ok();
-                }
+                // Models expire on their own, so this is polled more often than the model list.
+                let running_models = client.ps().await.unwrap_or_default();
 
-                cx.background_executor().timer(Duration::from_mins(5)).await;
+                this.update(cx, |this, cx| {
+                    this.endpoints[index].running_models = running_models;
+                    this.merge_running_models(cx);
+                })
+                .ok();
+
+                cx.background_executor()
+                    .timer(Duration::from_secs(30))
+                    .await;
             }
-        })
-        .detach();
+        }))
+    }
+
+    /// Lists the models of all servers, keeping the first of each name.
+    fn merge_models(&mut self, cx: &mut Context<Self>) {
+        let mut models = Vec::<AvailableModel>::new();
+
+        for model in self.endpoints.iter().flat_map(|endpoint| &endpoint.models) {
+            if !models.iter().any(|merged| merged.name == model.name) {
+                models.push(model.clone());
+            }
+        }
+
+        self.models = models;
+        cx.emit(OllamaServiceEvent::ModelsChanged);
+        cx.notify();
+    }
+
+    fn merge_running_models(&mut self, cx: &mut Context<Self>) {
+        self.running_models = self
+            .endpoints
+            .iter()
+            .flat_map(|endpoint| endpoint.running_models.iter().cloned())
+            .collect();
+        cx.emit(OllamaServiceEvent::RunningModelsChanged);
+        cx.notify();
     }
 }
@@ -0,0 +1,139 @@
+use crate::ollama::{Client, ServeOptions, Server};
+use gpui::{div, prelude::*, px, App, Context, Entity, Task, Window};
+use gpui_component::label::Label;
+use gpui_component::{gray_600, ActiveTheme, WindowExt};
+use std::future::Future;
+use std::time::Duration;
+
+/// Runs `ollama serve` when the app is configured to manage Ollama and no server is running.
+pub struct OllamaSupervisor {
+    /// Only set when the app started the server itself.
+    server: Option<Server>,
+    starting: Option<Task<()>>,
+}
+
+impl OllamaSupervisor {
+    pub fn new() -> Self {
+        OllamaSupervisor {
+            server: None,
+            starting: None,
+        }
+    }
+
+    /// Starts `ollama serve` unless a server is already reachable, in which case it is left
+    /// alone and not stopped on exit.
+    pub fn start(&mut self, client: Client, options: ServeOptions, cx: &mut Context<Self>) {
+        self.stop(cx);
+
+        self.starting = Some(cx.spawn(async move |this, cx| {
+            if let Err(err) = client.version().await
+                && err.is_unreachable()
+            {
+                this.update(cx, |this, cx| {
+                    this.server = Some(Server::spawn(options));
+                    cx.notify();
+                })
+                .ok();
+            }
+        }));
+    }
+
+    /// Stops the server if the app started it.
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        self.starting = None;
+
+        // Dropping the server kills the process.
+        if self.server.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Stops the server like [`Self::stop`], and returns a future that resolves once the process
+    /// has exited, so the app can wait for it before quitting.
+    pub fn stop_and_wait(&mut self, cx: &mut Context<Self>) -> impl Future<Output = ()> + use<> {
+        self.starting = None;
+
+        let exited = self.server.take().map(|server| {
+            cx.notify();
+            server.stop()
+        });
+
+        async move {
+            if let Some(exited) = exited {
+                exited.await;
+            }
+        }
+    }
+
+    pub fn logs(&self) -> Vec<String> {
+        self.server
+            .as_ref()
+            .map(|server| server.logs())
+            .unwrap_or_default()
+    }
+}
+
+pub struct OllamaLogs {
+    supervisor: Entity<OllamaSupervisor>,
+    _refresh: Task<()>,
+}
+
+impl OllamaLogs {
+    pub fn new(supervisor: Entity<OllamaSupervisor>, cx: &mut Context<Self>) -> Self {
+        // The log is not observable, so it is re-read while the dialog is open.
+        let refresh = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        OllamaLogs {
+            supervisor,
+            _refresh: refresh,
+        }
+    }
+}
+
+impl Render for OllamaLogs {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let logs = self.supervisor.read(cx).logs();
+
+        if logs.is_empty() {
+            return div().child(
+                Label::new(t!("ollama-serve.no-logs"))
+                    .text_sm()
+                    .text_color(gray_600()),
+            );
+        }
+
+        div().child(
+            div()
+                .id("ollama-logs")
+                .max_h(px(360.))
+                .overflow_y_scroll()
+                .child(
+                    Label::new(logs.join("\n"))
+                        .text_xs()
+                        .font_family(cx.theme().mono_font_family.clone()),
+                ),
+        )
+    }
+}
+
+pub fn open_ollama_logs_dialog(
+    supervisor: Entity<OllamaSupervisor>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let view = cx.new(|cx| OllamaLogs::new(supervisor, cx));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(t!("ollama-serve.logs").to_string())
+            .child(view.clone())
+    });
+}
@@ -0,0 +1,6 @@
+#[allow(clippy::module_inception)]
+mod openai;
+mod types;
+
+pub use openai::*;
+pub use types::*;
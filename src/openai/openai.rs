@@ -0,0 +1,318 @@
+use crate::http::HttpClient;
+use crate::ollama::{ConnectionOptions, Error};
+use crate::openai::types::ErrorResponse;
+pub use crate::openai::types::*;
+use futures_util::stream::BoxStream;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use reqwest::header::ACCEPT;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::io;
+use std::mem;
+use tokio::sync::mpsc;
+use tokio_util::codec::{FramedRead, LinesCodec};
+use tokio_util::io::StreamReader;
+use url::Url;
+
+/// A client for servers that implement the OpenAI API, such as llama.cpp's server or
+/// LM Studio.
+#[derive(Clone)]
+pub struct Client {
+    http: HttpClient,
+}
+
+impl Client {
+    /// Creates a client for a server, e.g. `http://127.0.0.1:8080`. A trailing `/v1` is
+    /// accepted too, since that is how most servers document their base URL.
+    pub fn new(base_url: Url) -> Self {
+        Client::with_client(base_url, reqwest::Client::new())
+    }
+
+    /// Creates a client with custom headers, certificates, proxy or timeouts, e.g. an
+    /// `Authorization` header holding an API key.
+    pub fn with_options(base_url: Url, options: &ConnectionOptions) -> Result<Self, Error> {
+        Ok(Client::with_client(base_url, options.build_client()?))
+    }
+
+    fn with_client(mut base_url: Url, client: reqwest::Client) -> Self {
+        let path = base_url.path().trim_end_matches('/');
+        let path = path.strip_suffix("/v1").unwrap_or(path).to_owned();
+        base_url.set_path(&path);
+
+        Client {
+            http: HttpClient::new(base_url, client),
+        }
+    }
+
+    pub fn base_url(&self) -> &Url {
+        self.http.base_url()
+    }
+
+    /// Sends the request on the runtime and turns non-success responses into errors.
+    async fn send(&self, request: RequestBuilder, model: Option<&str>) -> Result<Response, Error> {
+        self.http
+            .send(request, |status, text| {
+                error_from_status(status, text, model)
+            })
+            .await
+    }
+
+    fn sse<T>(&self, resp: Response) -> BoxStream<'static, Result<T, Error>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        read_events(&self.http, resp)
+    }
+}
+
+/// Reads a server-sent event stream on the runtime and forwards the data of each event
+/// until `[DONE]` or the end of the body. Events holding an OpenAI-style error become errors.
+///
+/// The body is read by a task that is aborted as soon as the returned stream is dropped.
+pub(crate) fn read_events<T>(
+    http: &HttpClient,
+    resp: Response,
+) -> BoxStream<'static, Result<T, Error>>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel(32);
+
+    let task = http.spawn(async move {
+        let stream = resp.bytes_stream().map_err(io::Error::other);
+
+        let reader = StreamReader::new(stream);
+        let mut lines = FramedRead::new(reader, LinesCodec::new());
+        let mut data = String::new();
+
+        loop {
+            let line = match lines.next().await {
+                Some(Ok(line)) => Some(line),
+                Some(Err(err)) => {
+                    tx.send(Err(err.into())).await.ok();
+                    break;
+                }
+                None => None,
+            };
+
+            // Events end with a blank line, and a trailing event may end with the body.
+            if let Some(line) = &line
+                && !line.is_empty()
+            {
+                if let Some(value) = line.strip_prefix("data:") {
+                    if !data.is_empty() {
+                        data.push('\n');
+                    }
+                    data.push_str(value.strip_prefix(' ').unwrap_or(value));
+                }
+                continue;
+            }
+
+            let event = mem::take(&mut data);
+
+            if event == "[DONE]" || (line.is_none() && event.is_empty()) {
+                break;
+            }
+
+            if !event.is_empty() {
+                let item = match serde_json::from_str::<ErrorResponse>(&event) {
+                    Ok(ErrorResponse { error }) => Err(Error::Stream(error.message)),
+                    Err(_) => serde_json::from_str::<T>(&event).map_err(Error::from),
+                };
+
+                if tx.send(item).await.is_err() {
+                    break;
+                }
+            }
+
+            if line.is_none() {
+                break;
+            }
+        }
+    });
+
+    stream::poll_fn(move |cx| {
+        let _task = &task;
+        rx.poll_recv(cx)
+    })
+    .boxed()
+}
+
+/// Builds an error from a non-success response, using the OpenAI error message when the body
+/// has one.
+pub(crate) fn error_from_status(status: StatusCode, body: &str, model: Option<&str>) -> Error {
+    if status == StatusCode::NOT_FOUND
+        && let Some(model) = model
+    {
+        return Error::ModelNotFound(model.to_owned());
+    }
+
+    let message = match serde_json::from_str::<ErrorResponse>(body) {
+        Ok(ErrorResponse { error }) => error.message,
+        Err(_) => body.trim().to_owned(),
+    };
+
+    Error::Status { status, message }
+}
+
+impl Client {
+    pub async fn models(&self) -> Result<Vec<ModelObject>, Error> {
+        let resp = self.send(self.http.get("v1/models"), None).await?;
+
+        Ok(self.http.json::<ModelsResponse>(resp).await?.data)
+    }
+
+    /// Starts a streamed chat completion.
+    pub async fn chat_completions(
+        &self,
+        mut request: ChatCompletionRequest,
+    ) -> Result<BoxStream<'static, Result<ChatCompletionChunk, Error>>, Error> {
+        request.stream = Some(true);
+
+        let resp = self
+            .send(
+                self.http
+                    .post("v1/chat/completions")
+                    .header(ACCEPT, "text/event-stream")
+                    .json(&request),
+                Some(&request.model),
+            )
+            .await?;
+
+        Ok(self.sse(resp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Starts a server that answers one request with the given raw HTTP response.
+    async fn respond_once(response: &'static str) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 8192];
+
+            assert!(socket.read(&mut buf).await.unwrap() > 0);
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        url
+    }
+
+    fn stub_request() -> ChatCompletionRequest {
+        ChatCompletionRequest::builder()
+            .model("stub")
+            .user("Hello, world!")
+            .build()
+    }
+
+    #[test]
+    fn test_client_base_url() {
+        for base_url in [
+            "http://127.0.0.1:8080",
+            "http://127.0.0.1:8080/v1",
+            "http://127.0.0.1:8080/v1/",
+        ] {
+            let client = Client::new(base_url.parse().unwrap());
+
+            assert_eq!(
+                client.base_url().join("v1/models").unwrap().as_str(),
+                "http://127.0.0.1:8080/v1/models"
+            );
+        }
+
+        let client = Client::new("https://lan/openai/v1".parse().unwrap());
+        assert_eq!(client.base_url().as_str(), "https://lan/openai/");
+    }
+
+    #[tokio::test]
+    async fn test_models() {
+        let url = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"object\":\"list\",\"data\":[{\"id\":\"translategemma-4b\",\"object\":\"model\"}]}",
+        )
+        .await;
+
+        let models = Client::new(url).models().await.unwrap();
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "translategemma-4b");
+    }
+
+    #[tokio::test]
+    async fn test_chat_completions() {
+        let url = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/event-stream\r\n\
+             Connection: close\r\n\r\n\
+             : keep-alive\n\n\
+             data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+             data: {\"choices\":[{\"delta\":{\"content\":\"你好\"}}]}\n\n\
+             data:{\"choices\":[{\"delta\":{\"content\":\"，世界\"},\"finish_reason\":null}]}\n\n\
+             data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n\
+             data: [DONE]\n\n\
+             data: {\"choices\":[{\"delta\":{\"content\":\"ignored\"}}]}\n\n",
+        )
+        .await;
+
+        let chunks = Client::new(url)
+            .chat_completions(stub_request())
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap().content().to_owned())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(chunks, ["", "你好", "，世界", ""]);
+    }
+
+    #[tokio::test]
+    async fn test_error() {
+        let url = respond_once(
+            "HTTP/1.1 400 Bad Request\r\n\
+             Content-Type: application/json\r\n\
+             Connection: close\r\n\r\n\
+             {\"error\":{\"message\":\"context length exceeded\",\"type\":\"invalid_request_error\"}}",
+        )
+        .await;
+
+        let err = Client::new(url)
+            .chat_completions(stub_request())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            Error::Status { status, message }
+                if status == StatusCode::BAD_REQUEST && message == "context length exceeded"
+        ));
+
+        let url = respond_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/event-stream\r\n\
+             Connection: close\r\n\r\n\
+             data: {\"error\":{\"message\":\"out of memory\"}}\n\n",
+        )
+        .await;
+
+        let mut result = Client::new(url)
+            .chat_completions(stub_request())
+            .await
+            .unwrap();
+        assert!(matches!(
+            result.next().await.unwrap(),
+            Err(Error::Stream(message)) if message == "out of memory"
+        ));
+        assert!(result.next().await.is_none());
+    }
+}
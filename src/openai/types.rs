@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<Message>,
+    /// Streaming is disabled by default, unlike Ollama.
+    pub stream: Option<bool>,
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub seed: Option<i64>,
+    pub max_tokens: Option<u64>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+impl Message {
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Message {
+            role,
+            content: content.into(),
+        }
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Message::new(Role::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Message::new(Role::User, content)
+    }
+}
+
+impl ChatCompletionRequest {
+    pub fn builder() -> ChatCompletionRequestBuilder {
+        ChatCompletionRequestBuilder(Default::default())
+    }
+}
+
+pub struct ChatCompletionRequestBuilder(ChatCompletionRequest);
+
+#[allow(dead_code)]
+impl ChatCompletionRequestBuilder {
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.0.model = model.into();
+        self
+    }
+
+    pub fn message(mut self, message: Message) -> Self {
+        self.0.messages.push(message);
+        self
+    }
+
+    pub fn system(self, content: impl Into<String>) -> Self {
+        self.message(Message::system(content))
+    }
+
+    pub fn user(self, content: impl Into<String>) -> Self {
+        self.message(Message::user(content))
+    }
+
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.0.stream = Some(stream);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.0.temperature = Some(temperature);
+        self
+    }
+
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        self.0.top_p = Some(top_p);
+        self
+    }
+
+    pub fn seed(mut self, seed: i64) -> Self {
+        self.0.seed = Some(seed);
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u64) -> Self {
+        self.0.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn build(self) -> ChatCompletionRequest {
+        self.0
+    }
+}
+
+/// One server-sent event of a streamed chat completion.
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub choices: Vec<ChunkChoice>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct ChunkChoice {
+    #[serde(default)]
+    pub delta: Delta,
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Delta {
+    pub content: Option<String>,
+}
+
+impl ChatCompletionChunk {
+    /// Returns the text added by this chunk.
+    pub fn content(&self) -> &str {
+        self.choices
+            .first()
+            .and_then(|choice| choice.delta.content.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModelsResponse {
+    pub data: Vec<ModelObject>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelObject {
+    pub id: String,
+}
+
+/// The body of an OpenAI-style error, e.g. `{"error": {"message": "model not found"}}`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorResponse {
+    pub error: ErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorDetail {
+    pub message: String,
+}
@@ -1,16 +1,55 @@
 use crate::editor::{Editor, InputStateEntityExt};
-use gpui::{prelude::*, App, Context, Entity, EntityInputHandler, SharedString, Window};
+use crate::error::describe_ollama_error;
+use crate::glossary::MissingTerm;
+use crate::language::Direction;
+use crate::masking::MaskReport;
+use crate::ollama;
+use gpui::{
+    div, prelude::*, App, Context, Entity, EntityInputHandler, EventEmitter, SharedString, Window,
+};
+use gpui_component::button::Button;
 use gpui_component::input::InputState;
+use gpui_component::label::Label;
+use gpui_component::{
+    gray_600, red_200, red_50, yellow_200, yellow_50, yellow_600, ActiveTheme, Icon, IconName,
+    Sizable, StyledExt,
+};
+
+#[derive(Debug, Clone)]
+struct TranslateError {
+    summary: SharedString,
+    message: SharedString,
+    fix: SharedString,
+}
 
 pub struct OutputEditor {
     pub state: Entity<InputState>,
+    error: Option<TranslateError>,
+    /// Glossary entries whose translation is missing from the output.
+    missing_terms: Vec<MissingTerm>,
+    /// Protected text that went missing or was duplicated in the output.
+    mask_report: MaskReport,
+    /// The direction of the target language.
+    direction: Direction,
 }
 
+pub enum OutputEditorEvent {
+    Retry,
+}
+
+impl EventEmitter<OutputEditorEvent> for OutputEditor {}
+
 impl OutputEditor {
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> OutputEditor {
         let state = cx.new(|cx| InputState::new(window, cx).multi_line(true));
 
-        OutputEditor { state }
+        OutputEditor {
+            state,
+            error: None,
+            missing_terms: Vec::new(),
+            mask_report: MaskReport::default(),
+            direction: Direction::default(),
+        }
     }
 
     #[allow(dead_code)]
@@ -49,10 +88,184 @@ impl OutputEditor {
             this.replace_text_in_range(Some(end..end), text.as_ref(), window, cx);
         });
     }
+
+    /// Shows the error above any partial output and offers to retry.
+    pub fn show_error(
+        &mut self,
+        error: &ollama::Error,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (summary, fix) = describe_ollama_error(error);
+
+        self.error = Some(TranslateError {
+            summary,
+            message: error.to_string().into(),
+            fix,
+        });
+
+        self.state.update(cx, |this, cx| {
+            this.set_placeholder("", window, cx);
+        });
+
+        cx.notify();
+    }
+
+    pub fn clear_error(&mut self, cx: &mut Context<Self>) {
+        if self.error.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    pub fn set_missing_terms(&mut self, terms: Vec<MissingTerm>, cx: &mut Context<Self>) {
+        if self.missing_terms != terms {
+            self.missing_terms = terms;
+            cx.notify();
+        }
+    }
+
+    pub fn set_mask_report(&mut self, report: MaskReport, cx: &mut Context<Self>) {
+        if self.mask_report != report {
+            self.mask_report = report;
+            cx.notify();
+        }
+    }
+
+    pub fn set_direction(&mut self, direction: Direction, cx: &mut Context<Self>) {
+        if self.direction != direction {
+            self.direction = direction;
+            cx.notify();
+        }
+    }
+
+    fn render_missing_terms(&self) -> impl IntoElement {
+        let terms = self
+            .missing_terms
+            .iter()
+            .map(|MissingTerm { entry, sentence }| {
+                let term = if entry.do_not_translate {
+                    t!("glossary.missing.keep", source = entry.source)
+                } else {
+                    t!(
+                        "glossary.missing.expected",
+                        source = entry.source,
+                        target = entry.target
+                    )
+                };
+
+                match sentence {
+                    Some(sentence) => t!("glossary.missing.in", term = term, sentence = sentence),
+                    None => term,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        render_warning(t!("glossary.missing-terms").into(), terms.into())
+    }
+
+    fn render_mask_report(&self) -> impl IntoElement {
+        let MaskReport {
+            missing,
+            duplicated,
+        } = &self.mask_report;
+
+        let mut details = Vec::new();
+
+        if !missing.is_empty() {
+            details.push(t!("masking.missing", tokens = missing.join(" ")));
+        }
+        if !duplicated.is_empty() {
+            details.push(t!("masking.duplicated", tokens = duplicated.join(" ")));
+        }
+
+        render_warning(t!("masking.report").into(), details.join("\n").into())
+    }
+
+    fn render_error(&self, error: TranslateError, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex_none()
+            .flex()
+            .flex_row()
+            .items_start()
+            .gap_2()
+            .m_2()
+            .p_2()
+            .rounded_lg()
+            .border_1()
+            .border_color(red_200())
+            .bg(red_50())
+            .child(
+                Icon::new(IconName::CircleX)
+                    .text_color(cx.theme().red)
+                    .size_4()
+                    .mt_0p5(),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(Label::new(error.summary).text_sm().font_semibold())
+                    .child(Label::new(error.message).text_xs().text_color(gray_600()))
+                    .child(Label::new(error.fix).text_xs().text_color(gray_600())),
+            )
+            .child(
+                Button::new("retry-button")
+                    .small()
+                    .label(t!("retry"))
+                    .on_click(cx.listener(|_, _, _, cx| {
+                        cx.emit(OutputEditorEvent::Retry);
+                    })),
+            )
+    }
 }
 
 impl Render for OutputEditor {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         Editor::new("output-editor", &self.state)
+            .direction(self.direction)
+            .when_some(self.error.clone(), |this, error| {
+                this.banner(self.render_error(error, cx))
+            })
+            .when(!self.missing_terms.is_empty(), |this| {
+                this.banner(self.render_missing_terms())
+            })
+            .when(!self.mask_report.is_empty(), |this| {
+                this.banner(self.render_mask_report())
+            })
     }
 }
+
+/// A warning above the output that doesn't stop the translation, e.g. a glossary term that was
+/// not applied.
+fn render_warning(title: SharedString, details: SharedString) -> impl IntoElement {
+    div()
+        .flex_none()
+        .flex()
+        .flex_row()
+        .items_start()
+        .gap_2()
+        .m_2()
+        .p_2()
+        .rounded_lg()
+        .border_1()
+        .border_color(yellow_200())
+        .bg(yellow_50())
+        .child(
+            Icon::new(IconName::TriangleAlert)
+                .text_color(yellow_600())
+                .size_4()
+                .mt_0p5(),
+        )
+        .child(
+            div()
+                .flex_1()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(Label::new(title).text_sm().font_semibold())
+                .child(Label::new(details).text_xs().text_color(gray_600())),
+        )
+}
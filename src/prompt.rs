@@ -1,4 +1,8 @@
+use crate::glossary::{glossary_instructions, GlossaryEntry};
 use crate::language::Language;
+use crate::masking::MASKING_INSTRUCTION;
+use crate::prompt_template::{Placeholder, PromptTemplate};
+use crate::translation_style::TranslationStyle;
 use std::fmt::{Display, Formatter};
 use std::mem;
 
@@ -7,6 +11,16 @@ pub struct Prompt {
     pub source_language: Language,
     pub target_language: Language,
     pub text: String,
+    pub template: PromptTemplate,
+    /// The glossary entries whose term appears in the text, filled into `{glossary}` as
+    /// instructions.
+    pub glossary: Vec<GlossaryEntry>,
+    /// A description of the text, filled into `{context}`.
+    pub context: String,
+    /// Filled into `{style}` as instructions.
+    pub style: TranslationStyle,
+    /// The text has protected parts replaced by `⟦n⟧` tokens, which the model is told to keep.
+    pub masked: bool,
 }
 
 impl Prompt {
@@ -19,6 +33,11 @@ impl Prompt {
             source_language,
             target_language,
             text: text.into(),
+            template: PromptTemplate::default(),
+            glossary: Vec::new(),
+            context: String::new(),
+            style: TranslationStyle::default(),
+            masked: false,
         }
     }
 
@@ -36,6 +55,36 @@ impl Prompt {
 
         self
     }
+
+    pub fn template(mut self, template: PromptTemplate) -> Self {
+        self.template = template;
+
+        self
+    }
+
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context = context.into();
+
+        self
+    }
+
+    pub fn glossary(mut self, glossary: Vec<GlossaryEntry>) -> Self {
+        self.glossary = glossary;
+
+        self
+    }
+
+    pub fn style(mut self, style: TranslationStyle) -> Self {
+        self.style = style;
+
+        self
+    }
+
+    pub fn masked(mut self, masked: bool) -> Self {
+        self.masked = masked;
+
+        self
+    }
 }
 
 impl Display for Prompt {
@@ -44,20 +93,44 @@ impl Display for Prompt {
             source_language: source,
             target_language: target,
             text,
+            template,
+            glossary,
+            context,
+            style,
+            masked,
         } = self;
 
-        write!(
-            f,
-            "You are a professional {} ({}) to {} ({}) translator. Your goal is to accurately convey the meaning and nuances of the original {0} text while adhering to {2} grammar, vocabulary, and cultural sensitivities. Produce only the {2} translation, without any additional explanations or commentary. Please translate the following {0} text into {2}:\n\n\n{text}",
-            source.name, source.code, target.name, target.code,
-        )
+        let glossary = glossary_instructions(glossary);
+        let style = style.instructions(target.name);
+
+        let prompt = template.render(|placeholder| match placeholder {
+            Placeholder::SourceName => source.name,
+            Placeholder::SourceCode => source.code,
+            Placeholder::TargetName => target.name,
+            Placeholder::TargetCode => target.code,
+            Placeholder::Text => text,
+            Placeholder::Glossary => &glossary,
+            Placeholder::Context => context,
+            Placeholder::Style => &style,
+        });
+
+        // The instruction does not depend on the template, since the tokens come back as
+        // written only if the model keeps them.
+        if *masked {
+            writeln!(f, "{MASKING_INSTRUCTION}")?;
+        }
+
+        write!(f, "{prompt}")
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::glossary::GlossaryEntry;
     use crate::language::Language;
+    use crate::masking::{MaskingRules, MASKING_INSTRUCTION};
     use crate::prompt::Prompt;
+    use crate::translation_style::{Formality, TranslationStyle};
 
     #[test]
     fn test_prompt() {
@@ -76,4 +149,74 @@ mod tests {
             "You are a professional Chinese (zh-Hans) to English (en) translator. Your goal is to accurately convey the meaning and nuances of the original Chinese text while adhering to English grammar, vocabulary, and cultural sensitivities. Produce only the English translation, without any additional explanations or commentary. Please translate the following Chinese text into English:\n\n\n你好，世界！"
         );
     }
+
+    #[test]
+    fn test_style() {
+        let en = Language::new("en", "English");
+        let de = Language::new("de", "German");
+
+        let prompt = Prompt::new(en, de, "Sign in").style(TranslationStyle {
+            formality: Some(Formality::Formal),
+            ..Default::default()
+        });
+
+        assert!(prompt.to_string().starts_with(
+            "Use a formal register and the formal form of address in German.\nYou are a professional English (en) to German (de) translator."
+        ));
+    }
+
+    #[test]
+    fn test_context() {
+        let en = Language::new("en", "English");
+        let de = Language::new("de", "German");
+
+        let prompt = Prompt::new(en, de, "Save").context("UI strings of a photo editor");
+        assert!(prompt.to_string().starts_with(
+            "Context: UI strings of a photo editor\nYou are a professional English (en) to German (de) translator."
+        ));
+
+        let prompt = prompt
+            .template("Translate into {target_name}:\n{text}".parse().unwrap())
+            .style(TranslationStyle {
+                formality: Some(Formality::Formal),
+                ..Default::default()
+            });
+        assert_eq!(
+            prompt.to_string(),
+            "Use a formal register and the formal form of address in German.\nContext: UI strings of a photo editor\nTranslate into German:\nSave"
+        );
+    }
+
+    #[test]
+    fn test_masked() {
+        let en = Language::new("en", "English");
+        let de = Language::new("de", "German");
+
+        let masked = MaskingRules::default().mask("Hello {name}");
+        let prompt = Prompt::new(en, de, masked.text).masked(!masked.tokens.is_empty());
+
+        assert_eq!(
+            prompt.to_string(),
+            format!(
+                "{MASKING_INSTRUCTION}\nYou are a professional English (en) to German (de) translator. Your goal is to accurately convey the meaning and nuances of the original English text while adhering to German grammar, vocabulary, and cultural sensitivities. Produce only the German translation, without any additional explanations or commentary. Please translate the following English text into German:\n\n\nHello ⟦1⟧"
+            )
+        );
+
+        let masked = MaskingRules::default().mask("Hello");
+        let prompt = Prompt::new(en, de, masked.text).masked(!masked.tokens.is_empty());
+        assert!(!prompt.to_string().contains(MASKING_INSTRUCTION));
+    }
+
+    #[test]
+    fn test_glossary() {
+        let en = Language::new("en", "English");
+        let de = Language::new("de", "German");
+
+        let prompt = Prompt::new(en, de, "Pay the invoice")
+            .glossary(vec![GlossaryEntry::new("invoice", "Rechnung")]);
+
+        assert!(prompt.to_string().starts_with(
+            "Translate the following terms exactly as given, keeping the terms marked as such unchanged:\n- invoice → Rechnung\nYou are a professional English (en) to German (de) translator."
+        ));
+    }
 }
@@ -0,0 +1,302 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The built-in template, the prompt TranslateGemma was trained with. The style, glossary and
+/// context come first, on lines of their own, so that the prompt is unchanged without them.
+pub const DEFAULT_TEMPLATE: &str = "{style}\n{glossary}\nContext: {context}\nYou are a professional {source_name} ({source_code}) to {target_name} ({target_code}) translator. Your goal is to accurately convey the meaning and nuances of the original {source_name} text while adhering to {target_name} grammar, vocabulary, and cultural sensitivities. Produce only the {target_name} translation, without any additional explanations or commentary. Please translate the following {source_name} text into {target_name}:\n\n\n{text}";
+
+/// A variable of a prompt template, written as `{name}`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Placeholder {
+    SourceName,
+    SourceCode,
+    TargetName,
+    TargetCode,
+    Text,
+    Glossary,
+    Context,
+    Style,
+}
+
+impl Placeholder {
+    pub const ALL: [Placeholder; 8] = [
+        Placeholder::SourceName,
+        Placeholder::SourceCode,
+        Placeholder::TargetName,
+        Placeholder::TargetCode,
+        Placeholder::Text,
+        Placeholder::Glossary,
+        Placeholder::Context,
+        Placeholder::Style,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Placeholder::SourceName => "source_name",
+            Placeholder::SourceCode => "source_code",
+            Placeholder::TargetName => "target_name",
+            Placeholder::TargetCode => "target_code",
+            Placeholder::Text => "text",
+            Placeholder::Glossary => "glossary",
+            Placeholder::Context => "context",
+            Placeholder::Style => "style",
+        }
+    }
+
+    /// Optional placeholders may be empty. A line with an empty one is left out, so that
+    /// e.g. `Glossary: {glossary}` disappears when there is no glossary, unless the line holds
+    /// the text too.
+    pub fn is_optional(self) -> bool {
+        matches!(
+            self,
+            Placeholder::Glossary | Placeholder::Context | Placeholder::Style
+        )
+    }
+}
+
+/// The lines of the optional placeholders as in the built-in template. Templates that leave a
+/// placeholder out get its line in front, so that the settings it carries are not lost.
+const SECTIONS: [(Placeholder, &str); 3] = [
+    (Placeholder::Style, "{style}\n"),
+    (Placeholder::Glossary, "{glossary}\n"),
+    (Placeholder::Context, "Context: {context}\n"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A `{` without a matching `}`, or a `}` without a matching `{`. Literal braces are
+    /// written as `{{` and `}}`.
+    UnmatchedBrace,
+    UnknownPlaceholder(String),
+    /// Every template has to include the text to translate.
+    MissingText,
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::UnmatchedBrace => write!(f, "unmatched brace, write {{{{ or }}}}"),
+            TemplateError::UnknownPlaceholder(name) => write!(f, "unknown placeholder {{{name}}}"),
+            TemplateError::MissingText => write!(f, "missing {{text}}"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// Splits one line of a template into literal text and placeholders.
+fn parse_line(line: &str) -> Result<Vec<Segment>, TemplateError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => return Err(TemplateError::UnmatchedBrace),
+                        Some(c) => name.push(c),
+                    }
+                }
+
+                let placeholder = Placeholder::ALL
+                    .into_iter()
+                    .find(|placeholder| placeholder.name() == name.trim())
+                    .ok_or(TemplateError::UnknownPlaceholder(name))?;
+
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Placeholder(placeholder));
+            }
+            '}' => return Err(TemplateError::UnmatchedBrace),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+/// A prompt with placeholders for the languages and the text, e.g.
+/// `Translate from {source_name} to {target_name}:\n\n{text}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptTemplate {
+    source: String,
+    lines: Vec<Vec<Segment>>,
+}
+
+impl PromptTemplate {
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.source == DEFAULT_TEMPLATE
+    }
+
+    /// Fills in the placeholders. Lines with an empty optional placeholder are left out,
+    /// including the ones added for optional placeholders the template leaves out. The line
+    /// with the text is always kept, with the empty placeholders left blank.
+    pub fn render<'a>(&self, value: impl Fn(Placeholder) -> &'a str) -> String {
+        let mut output = String::new();
+
+        for line in &self.lines {
+            let has_text = line.contains(&Segment::Placeholder(Placeholder::Text));
+            let skip = !has_text
+                && line.iter().any(|segment| {
+                    matches!(segment, Segment::Placeholder(placeholder)
+                        if placeholder.is_optional() && value(*placeholder).trim().is_empty())
+                });
+
+            if skip {
+                continue;
+            }
+
+            for segment in line {
+                match segment {
+                    Segment::Literal(literal) => output.push_str(literal),
+                    Segment::Placeholder(placeholder) => output.push_str(value(*placeholder)),
+                }
+            }
+        }
+
+        output
+    }
+}
+
+impl Default for PromptTemplate {
+    fn default() -> Self {
+        DEFAULT_TEMPLATE.parse().expect("invalid default template")
+    }
+}
+
+impl FromStr for PromptTemplate {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .split_inclusive('\n')
+            .map(parse_line)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let contains = |placeholder: Placeholder| {
+            lines
+                .iter()
+                .flatten()
+                .any(|segment| segment == &Segment::Placeholder(placeholder))
+        };
+
+        if !contains(Placeholder::Text) {
+            return Err(TemplateError::MissingText);
+        }
+
+        let sections = SECTIONS
+            .into_iter()
+            .filter(|(placeholder, _)| !contains(*placeholder))
+            .map(|(_, line)| parse_line(line).expect("invalid section"))
+            .collect::<Vec<_>>();
+
+        Ok(PromptTemplate {
+            source: s.to_owned(),
+            lines: sections.into_iter().chain(lines).collect(),
+        })
+    }
+}
+
+impl Display for PromptTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, glossary: &str) -> Result<String, TemplateError> {
+        Ok(template
+            .parse::<PromptTemplate>()?
+            .render(|placeholder| match placeholder {
+                Placeholder::SourceName => "English",
+                Placeholder::SourceCode => "en",
+                Placeholder::TargetName => "Chinese",
+                Placeholder::TargetCode => "zh-Hans",
+                Placeholder::Text => "Hello, world!",
+                Placeholder::Glossary => glossary,
+                Placeholder::Context | Placeholder::Style => "",
+            }))
+    }
+
+    #[test]
+    fn test_render() {
+        assert!(PromptTemplate::default().is_default());
+
+        assert_eq!(
+            render("{source_name} -> {target_name} ({target_code}): {text}", ""),
+            Ok("English -> Chinese (zh-Hans): Hello, world!".to_owned())
+        );
+        assert_eq!(render("{{{text}}}", ""), Ok("{Hello, world!}".to_owned()));
+        assert_eq!(
+            render("{text}", "world = 世界"),
+            Ok("world = 世界\nHello, world!".to_owned())
+        );
+
+        let template =
+            "Translate into {target_name}.\nGlossary: {glossary}\nContext: {context}\n\n{text}";
+        assert_eq!(
+            render(template, ""),
+            Ok("Translate into Chinese.\n\nHello, world!".to_owned())
+        );
+        assert_eq!(
+            render(template, "world = 世界"),
+            Ok("Translate into Chinese.\nGlossary: world = 世界\n\nHello, world!".to_owned())
+        );
+
+        // The text is kept even when an optional placeholder on its line is empty.
+        let template = "Translate into {target_name}: {text} {glossary}";
+        assert_eq!(
+            render(template, ""),
+            Ok("Translate into Chinese: Hello, world! ".to_owned())
+        );
+        assert_eq!(
+            render(template, "world = 世界"),
+            Ok("Translate into Chinese: Hello, world! world = 世界".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(render("{text", ""), Err(TemplateError::UnmatchedBrace));
+        assert_eq!(render("text}", ""), Err(TemplateError::UnmatchedBrace));
+        assert_eq!(
+            render("{source} {text}", ""),
+            Err(TemplateError::UnknownPlaceholder("source".to_owned()))
+        );
+        assert_eq!(
+            render("Translate into {target_name}.", ""),
+            Err(TemplateError::MissingText)
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use crate::config::Config;
+use crate::language::Language;
+use crate::language_pair::PairSettings;
+use crate::prompt::Prompt;
+use crate::prompt_template::{Placeholder, PromptTemplate, DEFAULT_TEMPLATE};
+use gpui::{div, prelude::*, px, App, Entity, SharedString, Window};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::label::Label;
+use gpui_component::notification::Notification;
+use gpui_component::{ActiveTheme, Sizable, WindowExt};
+use std::collections::BTreeMap;
+
+pub struct PromptTemplateForm {
+    source: Language,
+    target: Language,
+    /// The text shown in the preview, the current input when there is one.
+    sample: String,
+    /// The saved templates, with the changes made in the dialog.
+    templates: BTreeMap<String, PromptTemplate>,
+    settings: PairSettings,
+    name: Entity<InputState>,
+    template: Entity<InputState>,
+    context: Entity<InputState>,
+}
+
+impl PromptTemplateForm {
+    pub fn new(
+        source: Language,
+        target: Language,
+        sample: String,
+        templates: BTreeMap<String, PromptTemplate>,
+        settings: PairSettings,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name = settings
+            .template
+            .clone()
+            .filter(|name| templates.contains_key(name))
+            .unwrap_or_default();
+        let template = templates
+            .get(&name)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| DEFAULT_TEMPLATE.to_owned());
+
+        let name = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("prompt-template.built-in"))
+                .default_value(name)
+        });
+        let template = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(6)
+                .default_value(template)
+        });
+        let context = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("prompt-template.context.placeholder"))
+                .default_value(settings.context.clone().unwrap_or_default())
+        });
+
+        // Keep the preview and the template buttons up to date.
+        for input in [&name, &template, &context] {
+            cx.subscribe(input, |_, _, event: &InputEvent, cx| {
+                if matches!(event, InputEvent::Change) {
+                    cx.notify();
+                }
+            })
+            .detach();
+        }
+
+        PromptTemplateForm {
+            source,
+            target,
+            sample,
+            templates,
+            settings,
+            name,
+            template,
+            context,
+        }
+    }
+
+    fn name(&self, cx: &App) -> String {
+        self.name.read(cx).value().trim().to_owned()
+    }
+
+    /// Loads a saved template into the editor, or the built-in one for `None`.
+    fn load(&mut self, name: Option<String>, window: &mut Window, cx: &mut Context<Self>) {
+        let template = name
+            .as_ref()
+            .and_then(|name| self.templates.get(name))
+            .map(ToString::to_string)
+            .unwrap_or_else(|| DEFAULT_TEMPLATE.to_owned());
+
+        self.name.update(cx, |this, cx| {
+            this.set_value(name.unwrap_or_default(), window, cx);
+        });
+        self.template.update(cx, |this, cx| {
+            this.set_value(template, window, cx);
+        });
+    }
+
+    fn delete(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.templates.remove(&self.name(cx));
+        self.load(None, window, cx);
+    }
+
+    /// Renders the prompt for the current language pair and input.
+    fn preview(&self, cx: &App) -> Result<String, String> {
+        let template = self
+            .template
+            .read(cx)
+            .value()
+            .parse::<PromptTemplate>()
+            .map_err(|err| format!("{}: {err}", t!("prompt-template.invalid")))?;
+
+        Ok(Prompt::new(self.source, self.target, self.sample.clone())
+            .template(template)
+            .context(self.context.read(cx).value().trim())
+            .style(self.settings.style.clone())
+            .to_string())
+    }
+
+    /// Returns the templates and the settings of the language pair to save.
+    pub fn settings(
+        &self,
+        cx: &App,
+    ) -> Result<(BTreeMap<String, PromptTemplate>, PairSettings), String> {
+        let template = self
+            .template
+            .read(cx)
+            .value()
+            .parse::<PromptTemplate>()
+            .map_err(|err| format!("{}: {err}", t!("prompt-template.invalid")))?;
+
+        let name = self.name(cx);
+        let mut templates = self.templates.clone();
+
+        let template = if name.is_empty() {
+            // The built-in template cannot be changed, only saved under a new name.
+            if !template.is_default() {
+                return Err(t!("prompt-template.name-required").into_owned());
+            }
+            None
+        } else {
+            templates.insert(name.clone(), template);
+            Some(name)
+        };
+
+        let context = self.context.read(cx).value().trim().to_owned();
+
+        Ok((
+            templates,
+            PairSettings {
+                template,
+                context: (!context.is_empty()).then_some(context),
+                ..self.settings.clone()
+            },
+        ))
+    }
+}
+
+fn field(label: impl Into<SharedString>, child: impl IntoElement) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(Label::new(label).text_sm())
+        .child(child)
+}
+
+impl Render for PromptTemplateForm {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let name = self.name(cx);
+
+        let template_button = |id: usize, label: SharedString, selected: bool| {
+            let button = Button::new(("template", id)).small().label(label);
+
+            if selected {
+                button.primary()
+            } else {
+                button.outline()
+            }
+        };
+
+        let templates = div()
+            .flex()
+            .flex_row()
+            .flex_wrap()
+            .gap_1()
+            .child(
+                template_button(0, t!("prompt-template.built-in").into(), name.is_empty())
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.load(None, window, cx);
+                    })),
+            )
+            .children(self.templates.keys().enumerate().map(|(index, template)| {
+                let template = template.clone();
+
+                template_button(index + 1, template.clone().into(), template == name).on_click(
+                    cx.listener(move |this, _, window, cx| {
+                        this.load(Some(template.clone()), window, cx);
+                    }),
+                )
+            }));
+
+        let placeholders = Placeholder::ALL
+            .into_iter()
+            .map(|placeholder| format!("{{{}}}", placeholder.name()))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let preview = match self.preview(cx) {
+            Ok(preview) => Label::new(preview).font_family(cx.theme().mono_font_family.clone()),
+            Err(err) => Label::new(err).text_color(cx.theme().danger),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                Label::new(format!("{} → {}", self.source.name, self.target.name))
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(templates)
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .items_end()
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(field(t!("prompt-template.name"), Input::new(&self.name))),
+                    )
+                    .when(self.templates.contains_key(&name), |this| {
+                        this.child(
+                            Button::new("delete")
+                                .outline()
+                                .label(t!("prompt-template.delete"))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.delete(window, cx);
+                                })),
+                        )
+                    }),
+            )
+            .child(field(t!("prompt-template"), Input::new(&self.template)))
+            .child(
+                Label::new(t!(
+                    "prompt-template.placeholders",
+                    placeholders = placeholders
+                ))
+                .text_xs()
+                .text_color(cx.theme().muted_foreground),
+            )
+            .child(field(
+                t!("prompt-template.context"),
+                Input::new(&self.context),
+            ))
+            .child(field(
+                t!("prompt-template.preview"),
+                div()
+                    .id("prompt-preview")
+                    .max_h(px(160.))
+                    .overflow_y_scroll()
+                    .p_2()
+                    .rounded_md()
+                    .bg(cx.theme().muted)
+                    .child(preview.text_xs()),
+            ))
+    }
+}
+
+pub fn open_prompt_template_dialog(
+    config: Entity<Config>,
+    source: Language,
+    target: Language,
+    sample: String,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let templates = config.read(cx).prompt_templates();
+    let settings = config.read(cx).pair_settings(source.code, target.code);
+    let view = cx
+        .new(|cx| PromptTemplateForm::new(source, target, sample, templates, settings, window, cx));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        let view = view.clone();
+        let config = config.clone();
+
+        dialog
+            .confirm()
+            .title(t!("prompt-template").to_string())
+            .child(view.clone())
+            .on_ok(move |_, window, cx| {
+                let (templates, settings) = match view.read(cx).settings(cx) {
+                    Ok(settings) => settings,
+                    Err(err) => {
+                        window.push_notification(Notification::error(err), cx);
+                        return false;
+                    }
+                };
+
+                config.update(cx, |this, cx| {
+                    this.set_prompt_settings(templates, source.code, target.code, settings, cx);
+                });
+
+                true
+            })
+    });
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Values kept out of the main config file, such as `Authorization` headers. On Unix the file is
+/// only readable by the current user. On Windows it gets the permissions of the folder it is in,
+/// which for the default config folder in the user's profile keeps it from other standard users.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Secrets {
+    /// Headers for each Ollama endpoint, keyed by base URL.
+    pub headers: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Secrets {
+    /// Loads the secrets, treating a missing file as empty.
+    pub fn load(path: &Path) -> io::Result<Secrets> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Secrets::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the secrets, or removes the file when there are none.
+    pub fn store(&self, path: &Path) -> io::Result<()> {
+        if self.headers.is_empty() {
+            return match fs::remove_file(path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            };
+        }
+
+        let text = toml::to_string(self).map_err(io::Error::other)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Write to a new file, created with restricted permissions on Unix so the secrets are
+        // never readable by others, then replace the old file.
+        let tmp = path.with_extension("toml.tmp");
+        let _ = fs::remove_file(&tmp);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(&tmp)?;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_secrets() {
+        let dir = env::temp_dir().join(format!("test-secrets-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("secrets.toml");
+
+        assert_eq!(Secrets::load(&path).unwrap(), Secrets::default());
+
+        let secrets = Secrets {
+            headers: BTreeMap::from([(
+                "https://ollama.internal/".to_owned(),
+                BTreeMap::from([("Authorization".to_owned(), "Bearer token".to_owned())]),
+            )]),
+        };
+        secrets.store(&path).unwrap();
+        secrets.store(&path).unwrap();
+
+        assert_eq!(Secrets::load(&path).unwrap(), secrets);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        Secrets::default().store(&path).unwrap();
+        assert!(!path.exists());
+        Secrets::default().store(&path).unwrap();
+    }
+}
@@ -1,21 +1,249 @@
-use gpui::{div, prelude::*, App, IntoElement, Window};
+use crate::model_pull::format_size;
+use crate::ollama::{self, Feature, RunningModel};
+use crate::ollama_service::ConnectionState;
+use crate::{Refresh, UnloadModel};
+use gpui::{div, prelude::*, App, Corner, FocusHandle, IntoElement, Window};
+use gpui_component::button::{Button, ButtonVariants};
 use gpui_component::label::Label;
-use gpui_component::{gray_400, gray_600, green_500, ActiveTheme};
+use gpui_component::menu::DropdownMenu;
+use gpui_component::tooltip::Tooltip;
+use gpui_component::{
+    gray_400, gray_600, green_500, red_500, yellow_500, yellow_600, ActiveTheme, Icon, IconName,
+    Sizable,
+};
 use semver::Version;
+use std::time::Duration;
 
 #[derive(IntoElement)]
 pub struct StatusBar {
+    state: ConnectionState,
+    last_error: Option<String>,
+    /// The kind of the main server, e.g. `Ollama`.
+    server: &'static str,
     ollama_version: Option<Version>,
+    /// The label and state of each server, listed when there are several.
+    endpoints: Vec<(String, ConnectionState)>,
+    /// The model and context size of a server that serves a single model.
+    loaded_model: Option<(String, Option<u64>)>,
+    running_models: Vec<RunningModel>,
+    focus_handle: FocusHandle,
 }
 
 impl StatusBar {
-    pub(crate) fn new(ollama_version: Option<Version>) -> Self {
-        Self { ollama_version }
+    pub(crate) fn new(
+        state: ConnectionState,
+        last_error: Option<String>,
+        server: &'static str,
+        ollama_version: Option<Version>,
+        endpoints: Vec<(String, ConnectionState)>,
+        loaded_model: Option<(String, Option<u64>)>,
+        running_models: Vec<RunningModel>,
+        focus_handle: FocusHandle,
+    ) -> Self {
+        Self {
+            state,
+            last_error,
+            server,
+            ollama_version,
+            endpoints,
+            loaded_model,
+            running_models,
+            focus_handle,
+        }
     }
 }
 
+/// The connection indicator. Hovering shows the state and the last error, and clicking
+/// checks the connection right away.
+fn connection_status(
+    state: ConnectionState,
+    last_error: Option<String>,
+    server: &'static str,
+    ollama_version: Option<Version>,
+    endpoints: Vec<(String, ConnectionState)>,
+) -> impl IntoElement {
+    let color = match &state {
+        ConnectionState::Connecting => gray_400(),
+        ConnectionState::Connected => green_500(),
+        ConnectionState::Disconnected(_) => red_500(),
+        ConnectionState::Error(_) => yellow_500(),
+    };
+
+    let label = match ollama_version {
+        Some(version) if state == ConnectionState::Connected => format!("{server} {version}"),
+        _ => server.to_owned(),
+    };
+
+    let describe = |state: &ConnectionState| match state {
+        ConnectionState::Connecting => t!("connection.connecting", server = server).into_owned(),
+        ConnectionState::Connected => t!("connection.connected", server = server).into_owned(),
+        ConnectionState::Disconnected(reason) => {
+            t!("connection.disconnected", reason = reason).into_owned()
+        }
+        ConnectionState::Error(reason) => t!("connection.error", reason = reason).into_owned(),
+    };
+
+    let mut tooltip = describe(&state);
+
+    if endpoints.len() > 1 {
+        for (label, state) in &endpoints {
+            tooltip.push_str(&format!("\n{label}: {}", describe(state)));
+        }
+    }
+
+    // The reason is already shown while disconnected.
+    if matches!(
+        state,
+        ConnectionState::Connecting | ConnectionState::Connected
+    ) && let Some(error) = last_error
+    {
+        tooltip.push('\n');
+        tooltip.push_str(&t!("connection.last-error", error = error));
+    }
+
+    tooltip.push('\n');
+    tooltip.push_str(&t!("connection.click-to-refresh"));
+
+    div()
+        .id("connection-status")
+        .flex()
+        .flex_row()
+        .gap_1()
+        .items_center()
+        .cursor_pointer()
+        .child(Label::new("•").text_lg().text_color(color))
+        .child(Label::new(label).text_xs().text_color(gray_600()))
+        .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+        .on_click(|_, window, cx| window.dispatch_action(Box::new(Refresh), cx))
+}
+
+/// The model a llama.cpp server was started with, and its context size.
+fn loaded_model_label(model: String, context_size: Option<u64>) -> impl IntoElement {
+    let label = match context_size {
+        Some(context_size) => {
+            t!("loaded-model", model = model, context = context_size).into_owned()
+        }
+        None => model,
+    };
+
+    div()
+        .id("loaded-model")
+        .child(Label::new(label).text_xs().text_color(gray_600()))
+        .tooltip(|window, cx| Tooltip::new(t!("loaded-model.tooltip")).build(window, cx))
+}
+
+/// A warning shown when the server is too old for some features. Clicking opens the download
+/// page.
+fn compatibility_warning(version: &Version) -> Option<impl IntoElement> {
+    let features = ollama::unsupported_features(version);
+    if features.is_empty() {
+        return None;
+    }
+
+    let features = features
+        .into_iter()
+        .map(|feature| match feature {
+            Feature::Gemma3 => t!("compat.gemma3"),
+            Feature::StructuredOutputs => t!("compat.structured-outputs"),
+            Feature::Reasoning => t!("compat.reasoning"),
+            Feature::ReasoningEffort => t!("compat.reasoning-effort"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let tooltip = t!(
+        "compat.outdated",
+        version = version,
+        recommended = ollama::recommended_version(),
+        features = features
+    )
+    .into_owned();
+
+    Some(
+        div()
+            .id("compatibility-warning")
+            .flex()
+            .flex_row()
+            .gap_1()
+            .items_center()
+            .cursor_pointer()
+            .child(
+                Icon::new(IconName::TriangleAlert)
+                    .size_3()
+                    .text_color(yellow_600()),
+            )
+            .child(
+                Label::new(t!("compat.upgrade"))
+                    .text_xs()
+                    .text_color(yellow_600()),
+            )
+            .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+            .on_click(|_, _, cx| cx.open_url("https://ollama.com/download")),
+    )
+}
+
+/// A button listing the loaded models, with a menu for unloading them.
+fn running_models_button(
+    running_models: Vec<RunningModel>,
+    focus_handle: FocusHandle,
+) -> impl IntoElement {
+    let tooltip = running_models
+        .iter()
+        .map(|model| {
+            let mut line = format!(
+                "{} · {} · {}% GPU",
+                model.name,
+                format_size(model.size),
+                model.gpu_percentage()
+            );
+
+            if let Some(expires_in) = model.expires_in() {
+                line.push_str(" · ");
+                line.push_str(&t!(
+                    "running.expires",
+                    duration = format_duration(expires_in)
+                ));
+            }
+
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Button::new("running-models")
+        .xsmall()
+        .ghost()
+        .label(t!("running.count", count = running_models.len()))
+        .text_color(gray_600())
+        .tooltip(tooltip)
+        .dropdown_menu(move |mut menu, _, _| {
+            menu = menu.action_context(focus_handle.clone());
+
+            for model in &running_models {
+                menu = menu.menu(
+                    t!("running.unload", model = model.name),
+                    Box::new(UnloadModel::new(&model.name)),
+                );
+            }
+
+            menu
+        })
+        .anchor(Corner::BottomRight)
+}
+
 impl RenderOnce for StatusBar {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let StatusBar {
+            state,
+            last_error,
+            server,
+            ollama_version,
+            endpoints,
+            loaded_model,
+            running_models,
+            focus_handle,
+        } = self;
+
         div()
             .h_8()
             .w_full()
@@ -33,25 +261,38 @@ impl RenderOnce for StatusBar {
                     .flex_row()
                     .gap_1()
                     .items_center()
-                    .child(
-                        div()
-                            .flex()
-                            .flex_row()
-                            .gap_1()
-                            .items_center()
-                            .when_none(&self.ollama_version, |this| {
-                                this.child(Label::new("•").text_lg().text_color(gray_400()))
-                                    .child(Label::new("Ollama").text_xs().text_color(gray_600()))
-                            })
-                            .when_some(self.ollama_version, |this, version| {
-                                this.child(Label::new("•").text_lg().text_color(green_500()))
-                                    .child(
-                                        Label::new(format!("Ollama {version}"))
-                                            .text_xs()
-                                            .text_color(gray_600()),
-                                    )
-                            }),
-                    ),
+                    .children(
+                        loaded_model
+                            .map(|(model, context_size)| loaded_model_label(model, context_size)),
+                    )
+                    .when(!running_models.is_empty(), |this| {
+                        this.child(running_models_button(running_models, focus_handle))
+                    })
+                    .children(
+                        ollama_version
+                            .as_ref()
+                            .filter(|_| state == ConnectionState::Connected)
+                            .and_then(compatibility_warning),
+                    )
+                    .child(connection_status(
+                        state,
+                        last_error,
+                        server,
+                        ollama_version,
+                        endpoints,
+                    )),
             )
     }
 }
+
+/// Formats a duration coarsely, e.g. `4m` or `1h 5m`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    match (secs / 3600, secs % 3600 / 60) {
+        (0, 0) => format!("{secs}s"),
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
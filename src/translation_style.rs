@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Formality {
+    /// E.g. German "Sie" or French "vous".
+    Formal,
+    /// E.g. German "du" or French "tu".
+    Informal,
+}
+
+impl Formality {
+    pub const ALL: [Formality; 2] = [Formality::Formal, Formality::Informal];
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tone {
+    Neutral,
+    Friendly,
+    Professional,
+    Concise,
+}
+
+impl Tone {
+    pub const ALL: [Tone; 4] = [
+        Tone::Neutral,
+        Tone::Friendly,
+        Tone::Professional,
+        Tone::Concise,
+    ];
+
+    fn adjective(self) -> &'static str {
+        match self {
+            Tone::Neutral => "neutral",
+            Tone::Friendly => "friendly",
+            Tone::Professional => "professional",
+            Tone::Concise => "concise",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Domain {
+    Legal,
+    Medical,
+    Technical,
+    SoftwareUi,
+    Marketing,
+}
+
+impl Domain {
+    pub const ALL: [Domain; 5] = [
+        Domain::Legal,
+        Domain::Medical,
+        Domain::Technical,
+        Domain::SoftwareUi,
+        Domain::Marketing,
+    ];
+
+    fn instruction(self) -> &'static str {
+        match self {
+            Domain::Legal => {
+                "The text is a legal document. Use precise legal terminology and keep the wording unambiguous."
+            }
+            Domain::Medical => {
+                "The text is medical. Use accurate medical terminology and do not simplify clinical details."
+            }
+            Domain::Technical => {
+                "The text is technical documentation. Use standard technical terminology and keep code, commands and identifiers unchanged."
+            }
+            Domain::SoftwareUi => {
+                "The text consists of software user interface strings. Keep translations short and consistent, and keep placeholders, shortcuts and markup unchanged."
+            }
+            Domain::Marketing => {
+                "The text is marketing copy. Keep it persuasive and natural rather than literal."
+            }
+        }
+    }
+}
+
+/// How a language pair is translated, e.g. formal German for customer documents. Unset options
+/// leave the choice to the model.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationStyle {
+    pub formality: Option<Formality>,
+    pub tone: Option<Tone>,
+    /// Who the translation is for, e.g. `developers` or `children`.
+    pub audience: Option<String>,
+    pub domain: Option<Domain>,
+    /// Any other instruction, added as written.
+    pub instruction: Option<String>,
+}
+
+impl TranslationStyle {
+    pub fn is_empty(&self) -> bool {
+        self == &TranslationStyle::default()
+    }
+
+    /// Returns the style as instructions for the model, filled into `{style}`. Empty when no
+    /// option is set.
+    pub fn instructions(&self, target_name: &str) -> String {
+        let mut instructions = Vec::new();
+
+        match self.formality {
+            Some(Formality::Formal) => instructions.push(format!(
+                "Use a formal register and the formal form of address in {target_name}."
+            )),
+            Some(Formality::Informal) => instructions.push(format!(
+                "Use an informal register and the informal form of address in {target_name}."
+            )),
+            None => {}
+        }
+
+        if let Some(tone) = self.tone {
+            instructions.push(format!("Use a {} tone.", tone.adjective()));
+        }
+
+        if let Some(audience) = self.audience.as_deref().map(str::trim)
+            && !audience.is_empty()
+        {
+            instructions.push(format!("The intended audience is {audience}."));
+        }
+
+        if let Some(domain) = self.domain {
+            instructions.push(domain.instruction().to_owned());
+        }
+
+        if let Some(instruction) = self.instruction.as_deref().map(str::trim)
+            && !instruction.is_empty()
+        {
+            instructions.push(instruction.to_owned());
+        }
+
+        instructions.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instructions() {
+        assert_eq!(TranslationStyle::default().instructions("German"), "");
+
+        let style = TranslationStyle {
+            formality: Some(Formality::Formal),
+            tone: Some(Tone::Professional),
+            audience: Some(" customers ".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            style.instructions("German"),
+            "Use a formal register and the formal form of address in German. Use a professional tone. The intended audience is customers."
+        );
+
+        let style = TranslationStyle {
+            domain: Some(Domain::SoftwareUi),
+            instruction: Some("Use \"Sie\".".to_owned()),
+            ..Default::default()
+        };
+        assert!(style
+            .instructions("German")
+            .ends_with("unchanged. Use \"Sie\"."));
+    }
+
+    #[test]
+    fn test_serde() {
+        let style = toml::from_str::<TranslationStyle>(
+            "formality = \"informal\"\ndomain = \"software-ui\"",
+        )
+        .unwrap();
+
+        assert_eq!(style.formality, Some(Formality::Informal));
+        assert_eq!(style.domain, Some(Domain::SoftwareUi));
+        assert!(style.tone.is_none());
+    }
+}
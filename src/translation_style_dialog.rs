@@ -0,0 +1,259 @@
+use crate::config::Config;
+use crate::language::Language;
+use crate::translation_style::{Domain, Formality, Tone, TranslationStyle};
+use gpui::{div, prelude::*, App, Entity, SharedString, Window};
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::label::Label;
+use gpui_component::radio::RadioGroup;
+use gpui_component::{ActiveTheme, WindowExt};
+use std::borrow::Cow;
+use std::iter;
+
+fn formality_label(formality: Formality) -> Cow<'static, str> {
+    match formality {
+        Formality::Formal => t!("translation-style.formal"),
+        Formality::Informal => t!("translation-style.informal"),
+    }
+}
+
+fn tone_label(tone: Tone) -> Cow<'static, str> {
+    match tone {
+        Tone::Neutral => t!("translation-style.neutral"),
+        Tone::Friendly => t!("translation-style.friendly"),
+        Tone::Professional => t!("translation-style.professional"),
+        Tone::Concise => t!("translation-style.concise"),
+    }
+}
+
+fn domain_label(domain: Domain) -> Cow<'static, str> {
+    match domain {
+        Domain::Legal => t!("translation-style.legal"),
+        Domain::Medical => t!("translation-style.medical"),
+        Domain::Technical => t!("translation-style.technical"),
+        Domain::SoftwareUi => t!("translation-style.software-ui"),
+        Domain::Marketing => t!("translation-style.marketing"),
+    }
+}
+
+/// Returns a short description of the style for the button next to the language selectors,
+/// e.g. `Formal · Legal`.
+pub fn style_summary(style: &TranslationStyle) -> SharedString {
+    let mut parts = Vec::new();
+
+    parts.extend(style.formality.map(formality_label));
+    parts.extend(style.tone.map(tone_label));
+    parts.extend(style.domain.map(domain_label));
+
+    if style.audience.is_some() || style.instruction.is_some() {
+        parts.push("…".into());
+    }
+
+    if parts.is_empty() {
+        t!("translation-style").into()
+    } else {
+        parts.join(" · ").into()
+    }
+}
+
+/// A radio group with a Default option first, for a setting that may be unset.
+fn options<T: Copy + PartialEq + 'static>(
+    id: &'static str,
+    all: &[T],
+    selected: Option<T>,
+    label: fn(T) -> Cow<'static, str>,
+    cx: &Context<TranslationStyleForm>,
+    select: fn(&mut TranslationStyleForm, Option<T>),
+) -> impl IntoElement {
+    let all = all.to_vec();
+    let labels = iter::once(t!("translation-style.default"))
+        .chain(all.iter().map(|value| label(*value)))
+        .map(Cow::into_owned);
+
+    RadioGroup::horizontal(id)
+        .children(labels)
+        .selected_index(Some(
+            selected
+                .and_then(|selected| all.iter().position(|value| *value == selected))
+                .map_or(0, |index| index + 1),
+        ))
+        .on_click(cx.listener(move |this, index: &usize, _, cx| {
+            select(this, index.checked_sub(1).map(|index| all[index]));
+            cx.notify();
+        }))
+}
+
+pub struct TranslationStyleForm {
+    target: Language,
+    formality: Option<Formality>,
+    tone: Option<Tone>,
+    domain: Option<Domain>,
+    audience: Entity<InputState>,
+    instruction: Entity<InputState>,
+}
+
+impl TranslationStyleForm {
+    pub fn new(
+        target: Language,
+        style: TranslationStyle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let audience = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("translation-style.audience.placeholder"))
+                .default_value(style.audience.unwrap_or_default())
+        });
+        let instruction = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .rows(2)
+                .placeholder(t!("translation-style.instruction.placeholder"))
+                .default_value(style.instruction.unwrap_or_default())
+        });
+
+        // Keep the preview up to date.
+        for input in [&audience, &instruction] {
+            cx.subscribe(input, |_, _, event: &InputEvent, cx| {
+                if matches!(event, InputEvent::Change) {
+                    cx.notify();
+                }
+            })
+            .detach();
+        }
+
+        TranslationStyleForm {
+            target,
+            formality: style.formality,
+            tone: style.tone,
+            domain: style.domain,
+            audience,
+            instruction,
+        }
+    }
+
+    pub fn style(&self, cx: &App) -> TranslationStyle {
+        let text = |input: &Entity<InputState>| {
+            Some(input.read(cx).value().trim().to_owned()).filter(|text| !text.is_empty())
+        };
+
+        TranslationStyle {
+            formality: self.formality,
+            tone: self.tone,
+            audience: text(&self.audience),
+            domain: self.domain,
+            instruction: text(&self.instruction),
+        }
+    }
+}
+
+fn field(label: impl Into<SharedString>, child: impl IntoElement) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(Label::new(label).text_sm())
+        .child(child)
+}
+
+impl Render for TranslationStyleForm {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let instructions = self.style(cx).instructions(self.target.name);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(field(
+                t!("translation-style.formality"),
+                options(
+                    "formality",
+                    &Formality::ALL,
+                    self.formality,
+                    formality_label,
+                    cx,
+                    |this, formality| this.formality = formality,
+                ),
+            ))
+            .child(field(
+                t!("translation-style.tone"),
+                options(
+                    "tone",
+                    &Tone::ALL,
+                    self.tone,
+                    tone_label,
+                    cx,
+                    |this, tone| this.tone = tone,
+                ),
+            ))
+            .child(field(
+                t!("translation-style.domain"),
+                options(
+                    "domain",
+                    &Domain::ALL,
+                    self.domain,
+                    domain_label,
+                    cx,
+                    |this, domain| this.domain = domain,
+                ),
+            ))
+            .child(field(
+                t!("translation-style.audience"),
+                Input::new(&self.audience),
+            ))
+            .child(field(
+                t!("translation-style.instruction"),
+                Input::new(&self.instruction),
+            ))
+            .when(!instructions.is_empty(), |this| {
+                this.child(
+                    Label::new(instructions)
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+    }
+}
+
+pub fn open_translation_style_dialog(
+    config: Entity<Config>,
+    source: Language,
+    target: Language,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let style = config
+        .read(cx)
+        .pair_settings(source.code, target.code)
+        .style;
+    let view = cx.new(|cx| TranslationStyleForm::new(target, style, window, cx));
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let view = view.clone();
+        let config = config.clone();
+
+        dialog
+            .confirm()
+            .title(t!("translation-style").to_string())
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        Label::new(format!("{} → {}", source.name, target.name))
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(view.clone()),
+            )
+            .on_ok(move |_, _, cx| {
+                let style = view.read(cx).style(cx);
+
+                config.update(cx, |this, cx| {
+                    this.set_translation_style(source.code, target.code, style, cx);
+                });
+
+                true
+            })
+    });
+}